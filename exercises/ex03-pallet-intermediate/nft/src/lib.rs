@@ -9,9 +9,25 @@ pub use pallet::*;
 mod tests;
 pub mod types;
 
-use frame_support::{ensure, sp_runtime::traits::Zero};
+use frame_support::{
+	ensure,
+	sp_runtime::traits::{AccountIdConversion, One, Zero},
+	traits::{
+		fungibles,
+		tokens::{Fortitude, Precision},
+		Get,
+	},
+	PalletId,
+};
 use types::*;
 
+pub type AssetIdOf<T> = <<T as Config>::Fractions as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::AssetId;
+pub type FractionBalanceOf<T> = <<T as Config>::Fractions as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -24,6 +40,13 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type MaxLength: Get<u32>;
+
+		/// The fungible assets used to represent shares of a fractionalized unique asset.
+		type Fractions: fungibles::Create<Self::AccountId> + fungibles::Mutate<Self::AccountId>;
+
+		/// The pallet id, used to derive the account holding fractionalized unique assets.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
 	}
 
 	#[pallet::pallet]
@@ -52,6 +75,12 @@ pub mod pallet {
 	/// Nonce for id of the next created asset
 	pub(super) type Nonce<T: Config> = StorageValue<_, UniqueAssetId, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn fractions)]
+	/// The share asset minted for each unique asset currently locked in the pallet account.
+	pub(super) type Fractions<T: Config> =
+		StorageMap<_, Blake2_128Concat, UniqueAssetId, FractionDetails<T>>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -73,6 +102,18 @@ pub mod pallet {
 			to: T::AccountId,
 			amount: u128,
 		},
+		/// A unique asset has been locked and split into fungible shares
+		Fractionalized {
+			asset_id: UniqueAssetId,
+			share_asset_id: AssetIdOf<T>,
+			owner: T::AccountId,
+			shares: FractionBalanceOf<T>,
+		},
+		/// All the shares of a unique asset have been burned and the asset released
+		Unified {
+			asset_id: UniqueAssetId,
+			owner: T::AccountId,
+		},
 	}
 
 	#[pallet::error]
@@ -83,6 +124,16 @@ pub mod pallet {
 		NotOwned,
 		/// Supply must be positive
 		NoSupply,
+		/// The signing account does not own the whole supply of this asset
+		NotWholeOwner,
+		/// Amount of shares must be positive
+		NoShares,
+		/// The asset is already locked in fractions
+		AlreadyFractionalized,
+		/// The asset is not locked in fractions
+		NotFractionalized,
+		/// The signing account does not own all the shares of this asset
+		NotAllShares,
 	}
 
 	#[pallet::call]
@@ -191,10 +242,108 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(Weight::default())]
+		pub fn fractionalize(
+			origin: OriginFor<T>,
+			asset_id: UniqueAssetId,
+			share_asset_id: AssetIdOf<T>,
+			shares: FractionBalanceOf<T>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let details = Self::unique_asset(asset_id).ok_or(Error::<T>::UnknownAssetId)?;
+
+			ensure!(!shares.is_zero(), Error::<T>::NoShares);
+			ensure!(
+				!Fractions::<T>::contains_key(asset_id),
+				Error::<T>::AlreadyFractionalized
+			);
+			ensure!(
+				Self::account(asset_id, origin.clone()) == details.supply,
+				Error::<T>::NotWholeOwner
+			);
+
+			let custody = Self::account_id();
+
+			// The pallet account is the admin of the share asset, so nobody can mint more shares
+			<T::Fractions as fungibles::Create<T::AccountId>>::create(
+				share_asset_id.clone(),
+				custody.clone(),
+				false,
+				One::one(),
+			)?;
+			<T::Fractions as fungibles::Mutate<T::AccountId>>::mint_into(
+				share_asset_id.clone(),
+				&origin,
+				shares,
+			)?;
+
+			Account::<T>::remove(asset_id, origin.clone());
+			Account::<T>::insert(asset_id, custody, details.supply);
+
+			Fractions::<T>::insert(
+				asset_id,
+				FractionDetails {
+					share_asset_id: share_asset_id.clone(),
+					shares,
+				},
+			);
+
+			Self::deposit_event(Event::Fractionalized {
+				asset_id,
+				share_asset_id,
+				owner: origin,
+				shares,
+			});
+
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(Weight::default())]
+		pub fn unify(origin: OriginFor<T>, asset_id: UniqueAssetId) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let fraction = Self::fractions(asset_id).ok_or(Error::<T>::NotFractionalized)?;
+
+			let owned_shares = <T::Fractions as fungibles::Inspect<T::AccountId>>::balance(
+				fraction.share_asset_id.clone(),
+				&origin,
+			);
+			ensure!(owned_shares >= fraction.shares, Error::<T>::NotAllShares);
+
+			<T::Fractions as fungibles::Mutate<T::AccountId>>::burn_from(
+				fraction.share_asset_id,
+				&origin,
+				fraction.shares,
+				Precision::Exact,
+				Fortitude::Polite,
+			)?;
+
+			let custody = Self::account_id();
+			let supply = Account::<T>::take(asset_id, custody);
+			Account::<T>::insert(asset_id, origin.clone(), supply);
+
+			Fractions::<T>::remove(asset_id);
+
+			Self::deposit_event(Event::Unified {
+				asset_id,
+				owner: origin,
+			});
+
+			Ok(())
+		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
+	/// The account holding the unique assets locked by `fractionalize`
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
 	fn ensure_own_some(asset_id: UniqueAssetId, account: T::AccountId) -> Result<(), Error<T>> {
 		ensure!(
 			!Self::account(asset_id, account).is_zero(),
@@ -0,0 +1,216 @@
+use crate::{tests::mock::*, Error};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin, traits::fungibles::Inspect};
+
+const SHARE_ASSET_ID: u32 = 7;
+
+fn last_event() -> RuntimeEvent {
+	frame_system::Pallet::<TestRuntime>::events()
+		.pop()
+		.expect("Event expected")
+		.event
+}
+
+fn mint_nft(supply: u128) {
+	assert_ok!(NFTs::mint(
+		RuntimeOrigin::signed(ALICE),
+		"Some metadata".as_bytes().to_vec().try_into().unwrap(),
+		supply
+	));
+}
+
+fn shares_of(account: u64) -> u128 {
+	Assets::balance(SHARE_ASSET_ID, account)
+}
+
+mod fractionalize {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_ok!(NFTs::fractionalize(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				SHARE_ASSET_ID,
+				1000
+			));
+
+			assert_eq!(NFTs::account(0, ALICE), 0);
+			assert_eq!(NFTs::account(0, NFTs::account_id()), 1);
+			assert_eq!(shares_of(ALICE), 1000);
+			assert_eq!(Assets::total_issuance(SHARE_ASSET_ID), 1000);
+
+			let fraction = NFTs::fractions(0).unwrap();
+			assert_eq!(fraction.share_asset_id, SHARE_ASSET_ID);
+			assert_eq!(fraction.shares, 1000);
+			assert_eq!(
+				last_event(),
+				RuntimeEvent::NFTs(crate::Event::Fractionalized {
+					asset_id: 0,
+					share_asset_id: SHARE_ASSET_ID,
+					owner: ALICE,
+					shares: 1000
+				})
+			)
+		})
+	}
+
+	#[test]
+	fn locked_asset_cannot_be_transferred() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_ok!(NFTs::fractionalize(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				SHARE_ASSET_ID,
+				1000
+			));
+
+			assert_noop!(
+				NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 1, BOB),
+				Error::<TestRuntime>::NotOwned
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				NFTs::fractionalize(RuntimeOrigin::none(), 0, SHARE_ASSET_ID, 1000),
+				BadOrigin
+			);
+		})
+	}
+
+	#[test]
+	fn must_exist() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				NFTs::fractionalize(RuntimeOrigin::signed(ALICE), 0, SHARE_ASSET_ID, 1000),
+				Error::<TestRuntime>::UnknownAssetId
+			);
+		})
+	}
+
+	#[test]
+	fn must_have_positive_shares() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_noop!(
+				NFTs::fractionalize(RuntimeOrigin::signed(ALICE), 0, SHARE_ASSET_ID, 0),
+				Error::<TestRuntime>::NoShares
+			);
+		})
+	}
+
+	#[test]
+	fn must_own_whole_supply() {
+		new_test_ext().execute_with(|| {
+			mint_nft(5);
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 1, BOB));
+			assert_noop!(
+				NFTs::fractionalize(RuntimeOrigin::signed(ALICE), 0, SHARE_ASSET_ID, 1000),
+				Error::<TestRuntime>::NotWholeOwner
+			);
+		})
+	}
+
+	#[test]
+	fn cannot_fractionalize_twice() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_ok!(NFTs::fractionalize(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				SHARE_ASSET_ID,
+				1000
+			));
+			assert_noop!(
+				NFTs::fractionalize(RuntimeOrigin::signed(ALICE), 0, SHARE_ASSET_ID + 1, 1000),
+				Error::<TestRuntime>::AlreadyFractionalized
+			);
+		})
+	}
+}
+
+mod unify {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_ok!(NFTs::fractionalize(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				SHARE_ASSET_ID,
+				1000
+			));
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				SHARE_ASSET_ID.into(),
+				BOB,
+				1000
+			));
+
+			assert_ok!(NFTs::unify(RuntimeOrigin::signed(BOB), 0));
+
+			assert_eq!(NFTs::account(0, NFTs::account_id()), 0);
+			assert_eq!(NFTs::account(0, BOB), 1);
+			assert_eq!(shares_of(BOB), 0);
+			assert_eq!(Assets::total_issuance(SHARE_ASSET_ID), 0);
+			assert!(NFTs::fractions(0).is_none());
+			assert_eq!(
+				last_event(),
+				RuntimeEvent::NFTs(crate::Event::Unified {
+					asset_id: 0,
+					owner: BOB
+				})
+			)
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(NFTs::unify(RuntimeOrigin::none(), 0), BadOrigin);
+		})
+	}
+
+	#[test]
+	fn must_be_fractionalized() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_noop!(
+				NFTs::unify(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::NotFractionalized
+			);
+		})
+	}
+
+	#[test]
+	fn must_own_all_shares() {
+		new_test_ext().execute_with(|| {
+			mint_nft(1);
+			assert_ok!(NFTs::fractionalize(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				SHARE_ASSET_ID,
+				1000
+			));
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				SHARE_ASSET_ID.into(),
+				BOB,
+				1
+			));
+
+			assert_noop!(
+				NFTs::unify(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::NotAllShares
+			);
+		})
+	}
+}
@@ -1,5 +1,6 @@
 use crate as pallet_nft;
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, traits::AsEnsureOriginWithArg, PalletId};
+use frame_system::{EnsureRoot, EnsureSigned};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -15,6 +16,9 @@ frame_support::construct_runtime!(
 	pub enum TestRuntime
 	{
 		System: frame_system,
+		Balances: pallet_balances,
+		Assets: pallet_assets,
+
 		NFTs: pallet_nft,
 	}
 );
@@ -26,7 +30,7 @@ parameter_types! {
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
 impl frame_system::Config for TestRuntime {
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type AccountId = u64;
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockHashCount = BlockHashCount;
@@ -50,18 +54,83 @@ impl frame_system::Config for TestRuntime {
 	type Version = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
+	pub const MaxFreezes: u32 = 100;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type AccountStore = System;
+	type Balance = u128;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = MaxFreezes;
+}
+
+parameter_types! {
+	pub const AssetDeposit: u128 = 0;
+	pub const AssetAccountDeposit: u128 = 0;
+	pub const MetadataDepositBase: u128 = 0;
+	pub const MetadataDepositPerByte: u128 = 0;
+	pub const ApprovalDeposit: u128 = 0;
+	pub const StringLimit: u32 = 50;
+	pub const RemoveItemsLimit: u32 = 1000;
+}
+
+impl pallet_assets::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u128;
+	type RemoveItemsLimit = RemoveItemsLimit;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+	type ForceOrigin = EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const MaxLength: u32 = 20;
+	pub const NFTsPalletId: PalletId = PalletId(*b"py/nftfr");
 }
 
 impl pallet_nft::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxLength = MaxLength;
+	type Fractions = Assets;
+	type PalletId = NFTsPalletId;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	let t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
+	let mut t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
+
+	// Accounts must exist to receive shares of a fractionalized asset
+	pallet_balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(ALICE, 100), (BOB, 100)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
 	let mut ext = sp_io::TestExternalities::new(t);
 	// In order to emit events the block number must be more than 0
 	ext.execute_with(|| System::set_block_number(1));
@@ -1,2 +1,3 @@
+pub mod fractions;
 pub mod mock;
 pub mod unique_assets;
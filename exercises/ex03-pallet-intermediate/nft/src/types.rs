@@ -1,4 +1,4 @@
-use crate::Config;
+use crate::{AssetIdOf, Config, FractionBalanceOf};
 use codec::{Decode, Encode};
 use frame_support::{
 	pallet_prelude::{BoundedVec, MaxEncodedLen, RuntimeDebug},
@@ -34,3 +34,10 @@ impl<T: Config, S: Get<u32>> UniqueAssetDetails<T, S> {
 		self.metadata.clone()
 	}
 }
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub struct FractionDetails<T: Config> {
+	pub share_asset_id: AssetIdOf<T>,
+	pub shares: FractionBalanceOf<T>,
+}
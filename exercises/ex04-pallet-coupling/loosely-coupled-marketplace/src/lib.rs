@@ -2,15 +2,31 @@
 
 pub use pallet::*;
 
+pub mod runtime_api;
 #[cfg(test)]
 mod tests;
 pub mod types;
 
 use frame_support::{
+	dispatch::DispatchResult,
 	ensure,
-	sp_runtime::traits::{CheckedConversion, CheckedMul},
-	traits::{Currency, ExistenceRequirement::KeepAlive},
+	sp_runtime::{
+		traits::{AccountIdConversion, CheckedAdd, CheckedConversion, CheckedMul, Zero},
+		DispatchError, Permill,
+	},
+	storage::{self, TransactionOutcome},
+	traits::{
+		fungibles,
+		tokens::{Precision, Preservation},
+		BalanceStatus, Currency,
+		ExistenceRequirement::KeepAlive,
+		Get, ReservableCurrency,
+	},
+	weights::Weight,
+	PalletId,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_std::vec::Vec;
 // use support::Sellable;
 use types::*;
 
@@ -18,6 +34,8 @@ use pallet_marketplace_nfts::types::Sellable;
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type AssetIdOf<T> =
+	<<T as Config>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -28,7 +46,9 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config + scale_info::TypeInfo {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
+		// Assets that fixed price and Dutch sales can be priced in, besides the native currency
+		type Assets: fungibles::Mutate<Self::AccountId, Balance = BalanceOf<Self>>;
 		// Here are types that allow for the pallet coupling.
 		// Resource must be a type that implements transferable (remember that pallets are types).
 		// ResourceId is used to have a fully generic resource, can be int, uint, string, hash
@@ -37,6 +57,33 @@ pub mod pallet {
 		// it will still be compatible with this one.
 		type ResourceId: Parameter + Copy + MaxEncodedLen;
 		type Resource: Sellable<Self::AccountId, Self::ResourceId>;
+
+		// The number of auctions that can end in the same block, which bounds the weight of the
+		// settlement done in `on_initialize`
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+
+		// The number of listings that can be bought from in a single `buy_many`
+		#[pallet::constant]
+		type MaxPurchases: Get<u32>;
+
+		// The number of fixed price listings of a resource, in a given asset, that can be indexed
+		#[pallet::constant]
+		type MaxAsksPerResource: Get<u32>;
+
+		// The share of every fixed price or Dutch sale that is taken by the marketplace
+		#[pallet::constant]
+		type MarketplaceFee: Get<Permill>;
+
+		// The account receiving the marketplace fees. It must be kept above the existential
+		// deposit, of the native currency and of every asset sold for, as paying it a fee too
+		// small to open the account fails the sale.
+		#[pallet::constant]
+		type FeeDestination: Get<Self::AccountId>;
+
+		// Used to derive the account holding the resources of escrowed sales
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
 	}
 
 	#[pallet::pallet]
@@ -45,10 +92,85 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Resource has been listed for sale (resource_id, seller, price, amount)
-		ListedForSale(T::ResourceId, T::AccountId, BalanceOf<T>, u128),
-		// Resource has been sold (resource_id, seller, buyer, amount)
-		Sold(T::ResourceId, T::AccountId, T::AccountId, u128),
+		/// Resource has been listed for sale (resource_id, seller, price, amount, asset)
+		ListedForSale(
+			T::ResourceId,
+			T::AccountId,
+			BalanceOf<T>,
+			u128,
+			AssetKind<AssetIdOf<T>>,
+		),
+		/// Resource has been sold (resource_id, seller, buyer, amount, paid_to_seller, fee)
+		Sold(
+			T::ResourceId,
+			T::AccountId,
+			T::AccountId,
+			u128,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// Sale has been cancelled (resource_id, seller)
+		SaleCancelled(T::ResourceId, T::AccountId),
+		/// Sale price has been updated (resource_id, seller, price)
+		PriceUpdated(T::ResourceId, T::AccountId, BalanceOf<T>),
+		/// Resource has been moved to escrow and listed for sale (resource_id, seller, price,
+		/// amount, asset)
+		ListedForEscrowSale(
+			T::ResourceId,
+			T::AccountId,
+			BalanceOf<T>,
+			u128,
+			AssetKind<AssetIdOf<T>>,
+		),
+		/// Resource has been listed for sale at a declining price (resource_id, seller,
+		/// start_price, floor_price, decay_per_block, amount, asset)
+		ListedForDutchSale(
+			T::ResourceId,
+			T::AccountId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			u128,
+			AssetKind<AssetIdOf<T>>,
+		),
+		/// Auction has been created (auction_id, resource_id, seller, amount, reserve_price,
+		/// end_block)
+		AuctionCreated(
+			AuctionId,
+			T::ResourceId,
+			T::AccountId,
+			u128,
+			BalanceOf<T>,
+			BlockNumberFor<T>,
+		),
+		/// A bid has been placed on an auction (auction_id, bidder, value)
+		BidPlaced(AuctionId, T::AccountId, BalanceOf<T>),
+		/// Auction has been settled (auction_id, winner, paid_to_seller, fee)
+		AuctionSettled(AuctionId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Auction ended without any bid (auction_id)
+		AuctionUnsold(AuctionId),
+		/// Auction could not be settled, the best bid has been refunded (auction_id)
+		AuctionFailed(AuctionId),
+		/// An offer has been made (offer_id, resource_id, buyer, seller, price, amount, expiry)
+		OfferMade(
+			OfferId,
+			T::ResourceId,
+			T::AccountId,
+			Option<T::AccountId>,
+			BalanceOf<T>,
+			u128,
+			BlockNumberFor<T>,
+		),
+		/// An offer has been accepted (offer_id, seller, buyer, paid_to_seller, fee)
+		OfferAccepted(
+			OfferId,
+			T::AccountId,
+			T::AccountId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// An offer has been withdrawn and its funds unreserved (offer_id)
+		OfferWithdrawn(OfferId),
 	}
 
 	#[pallet::error]
@@ -58,6 +180,22 @@ pub mod pallet {
 		NotEnoughOwned,
 		SaleNotFound,
 		Overflow,
+		FloorAboveStartPrice,
+		NotFixedPrice,
+		AuctionNotFound,
+		AuctionEnded,
+		EndBlockInThePast,
+		TooManyAuctionsInBlock,
+		BidTooLow,
+		BidOnOwnAuction,
+		OfferNotFound,
+		OfferExpired,
+		OfferNotExpired,
+		NotOfferRecipient,
+		PriceCapExceeded,
+		AssetMismatch,
+		OfferNotFunded,
+		BidNotFunded,
 	}
 
 	#[pallet::storage]
@@ -69,9 +207,64 @@ pub mod pallet {
 		Blake2_128Concat,
 		T::AccountId,
 		SaleData<T>,
+		OptionQuery,
+	>;
+
+	/// Fixed price listings of a resource in an asset as (price, seller), cheapest first and in
+	/// listing order at equal price. Dutch sales are not indexed as their price changes every
+	/// block. Once full, a cheaper listing evicts the priciest one: evicted listings stay for sale
+	/// and are indexed again as the cheaper ones are removed.
+	#[pallet::storage]
+	#[pallet::getter(fn ask_index)]
+	pub type AskIndex<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::ResourceId,
+		Blake2_128Concat,
+		AssetKind<AssetIdOf<T>>,
+		BoundedVec<(BalanceOf<T>, T::AccountId), T::MaxAsksPerResource>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_auction_id)]
+	pub type NextAuctionId<T: Config> = StorageValue<_, AuctionId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auction)]
+	pub type Auctions<T: Config> = StorageMap<_, Blake2_128Concat, AuctionId, AuctionData<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auctions_ending_at)]
+	pub type AuctionsEndingAt<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<AuctionId, T::MaxAuctionsPerBlock>,
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn next_offer_id)]
+	pub type NextOfferId<T: Config> = StorageValue<_, OfferId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn offer)]
+	pub type Offers<T: Config> = StorageMap<_, Blake2_128Concat, OfferId, OfferData<T>>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let mut used_weight = T::DbWeight::get().reads_writes(1, 1);
+
+			for auction_id in AuctionsEndingAt::<T>::take(n) {
+				used_weight += Self::settle_auction(auction_id);
+			}
+
+			used_weight
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
@@ -81,57 +274,719 @@ pub mod pallet {
 			nft_id: T::ResourceId,
 			price: BalanceOf<T>,
 			amount: u128,
+			asset: AssetKind<AssetIdOf<T>>,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 
+			Self::list(
+				nft_id,
+				origin.clone(),
+				SaleData {
+					price,
+					amount,
+					pricing: Pricing::Fixed,
+					asset: asset.clone(),
+					custody: Custody::Reserved,
+				},
+			)?;
+
+			Self::deposit_event(Event::<T>::ListedForSale(
+				nft_id, origin, price, amount, asset,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(Weight::default())]
+		pub fn buy(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			seller: T::AccountId,
+			amount: u128,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+
+			Self::do_buy(nft_id, seller, buyer, amount)?;
+
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(Weight::default())]
+		pub fn cancel_sale(origin: OriginFor<T>, nft_id: T::ResourceId) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
+			let sale_data = ResourcesForSale::<T>::take(nft_id, seller.clone())
+				.ok_or(Error::<T>::SaleNotFound)?;
+
+			Self::release(nft_id, seller.clone(), &sale_data)?;
+			Self::unindex_ask(nft_id, &sale_data.asset, &seller);
+
+			Self::deposit_event(Event::<T>::SaleCancelled(nft_id, seller));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(Weight::default())]
+		pub fn update_price(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			price: BalanceOf<T>,
+		) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
+			let asset = ResourcesForSale::<T>::try_mutate(
+				nft_id,
+				seller.clone(),
+				|maybe_sale| -> Result<_, DispatchError> {
+					let sale_data = maybe_sale.as_mut().ok_or(Error::<T>::SaleNotFound)?;
+					ensure!(
+						matches!(sale_data.pricing, Pricing::Fixed),
+						Error::<T>::NotFixedPrice
+					);
+					sale_data.price = price;
+
+					Ok(sale_data.asset.clone())
+				},
+			)?;
+
+			Self::unindex_ask(nft_id, &asset, &seller);
+			Self::index_ask(nft_id, asset, seller.clone(), price);
+
+			Self::deposit_event(Event::<T>::PriceUpdated(nft_id, seller, price));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(Weight::default())]
+		pub fn create_auction(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			amount: u128,
+			reserve_price: BalanceOf<T>,
+			end_block: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
 			ensure!(amount > 0, Error::<T>::ZeroAmount);
+			ensure!(
+				end_block > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::EndBlockInThePast
+			);
 
-			let owned = T::Resource::amount_owned(nft_id, origin.clone());
+			T::Resource::reserve(nft_id, seller.clone(), amount)
+				.map_err(|_| Error::<T>::NotEnoughOwned)?;
 
-			ensure!(owned >= amount, Error::<T>::NotEnoughOwned);
+			let auction_id = Self::next_auction_id();
+			AuctionsEndingAt::<T>::try_append(end_block, auction_id)
+				.map_err(|_| Error::<T>::TooManyAuctionsInBlock)?;
+			Auctions::<T>::insert(
+				auction_id,
+				AuctionData {
+					resource_id: nft_id,
+					seller: seller.clone(),
+					amount,
+					reserve_price,
+					end_block,
+					best_bid: None,
+				},
+			);
+			NextAuctionId::<T>::put(auction_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
 
-			ResourcesForSale::<T>::insert(nft_id, origin.clone(), SaleData { price, amount });
+			Self::deposit_event(Event::<T>::AuctionCreated(
+				auction_id,
+				nft_id,
+				seller,
+				amount,
+				reserve_price,
+				end_block,
+			));
 
-			Self::deposit_event(Event::<T>::ListedForSale(nft_id, origin, price, amount));
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(Weight::default())]
+		pub fn bid(
+			origin: OriginFor<T>,
+			auction_id: AuctionId,
+			value: BalanceOf<T>,
+		) -> DispatchResult {
+			let bidder = ensure_signed(origin)?;
+
+			Auctions::<T>::try_mutate(auction_id, |maybe_auction| -> DispatchResult {
+				let auction = maybe_auction.as_mut().ok_or(Error::<T>::AuctionNotFound)?;
+
+				ensure!(
+					frame_system::Pallet::<T>::block_number() < auction.end_block,
+					Error::<T>::AuctionEnded
+				);
+				ensure!(auction.seller != bidder, Error::<T>::BidOnOwnAuction);
+				ensure!(value >= auction.reserve_price, Error::<T>::BidTooLow);
+
+				// The previous best bidder gets their funds back, they are outbid
+				if let Some((best_bidder, best_value)) = auction.best_bid.take() {
+					ensure!(value > best_value, Error::<T>::BidTooLow);
+					T::Currency::unreserve(&best_bidder, best_value);
+				}
+
+				T::Currency::reserve(&bidder, value)?;
+				auction.best_bid = Some((bidder.clone(), value));
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::BidPlaced(auction_id, bidder, value));
 
 			Ok(())
 		}
 
-		#[pallet::call_index(1)]
+		#[pallet::call_index(6)]
 		#[pallet::weight(Weight::default())]
-		pub fn buy(
+		pub fn set_dutch_sale(
 			origin: OriginFor<T>,
 			nft_id: T::ResourceId,
-			seller: T::AccountId,
+			start_price: BalanceOf<T>,
+			floor_price: BalanceOf<T>,
+			decay_per_block: BalanceOf<T>,
 			amount: u128,
+			asset: AssetKind<AssetIdOf<T>>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			ensure!(floor_price <= start_price, Error::<T>::FloorAboveStartPrice);
+
+			Self::list(
+				nft_id,
+				origin.clone(),
+				SaleData {
+					price: start_price,
+					amount,
+					pricing: Pricing::Dutch {
+						floor_price,
+						decay_per_block,
+						start_block: frame_system::Pallet::<T>::block_number(),
+					},
+					asset: asset.clone(),
+					custody: Custody::Reserved,
+				},
+			)?;
+
+			Self::deposit_event(Event::<T>::ListedForDutchSale(
+				nft_id,
+				origin,
+				start_price,
+				floor_price,
+				decay_per_block,
+				amount,
+				asset,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(Weight::default())]
+		pub fn make_offer(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			seller: Option<T::AccountId>,
+			price: BalanceOf<T>,
+			amount: u128,
+			expiry: BlockNumberFor<T>,
 		) -> DispatchResult {
 			let buyer = ensure_signed(origin)?;
 
-			let sale_data = ResourcesForSale::<T>::get(nft_id, seller.clone());
+			ensure!(amount > 0, Error::<T>::ZeroAmount);
+			ensure!(
+				expiry > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::OfferExpired
+			);
 
-			let owned = T::Resource::amount_owned(nft_id, seller.clone());
+			T::Currency::reserve(&buyer, Self::total_price(price, amount)?)?;
+
+			let offer_id = Self::next_offer_id();
+			Offers::<T>::insert(
+				offer_id,
+				OfferData {
+					resource_id: nft_id,
+					buyer: buyer.clone(),
+					seller: seller.clone(),
+					price,
+					amount,
+					expiry,
+				},
+			);
+			NextOfferId::<T>::put(offer_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
 
-			ensure!(amount <= sale_data.amount, Error::<T>::NotEnoughInSale);
+			Self::deposit_event(Event::<T>::OfferMade(
+				offer_id, nft_id, buyer, seller, price, amount, expiry,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(8)]
+		#[pallet::weight(Weight::default())]
+		pub fn accept_offer(origin: OriginFor<T>, offer_id: OfferId) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
+			let offer = Offers::<T>::take(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+
+			ensure!(
+				frame_system::Pallet::<T>::block_number() < offer.expiry,
+				Error::<T>::OfferExpired
+			);
+			ensure!(
+				offer.seller.as_ref().is_none_or(|recipient| *recipient == seller),
+				Error::<T>::NotOfferRecipient
+			);
+
+			// Listed amounts are reserved, so only the unlisted part can fill an offer
+			T::Resource::transfer(
+				offer.resource_id,
+				seller.clone(),
+				offer.buyer.clone(),
+				offer.amount,
+				Precision::Exact,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+			let (paid_to_seller, fee) = Self::pay_reserved(
+				&offer.buyer,
+				&seller,
+				Self::total_price(offer.price, offer.amount)?,
+				Error::<T>::OfferNotFunded,
+			)?;
+
+			Self::deposit_event(Event::<T>::OfferAccepted(
+				offer_id,
+				seller,
+				offer.buyer,
+				paid_to_seller,
+				fee,
+			));
+
+			Ok(())
+		}
+
+		/// Withdraw an offer, by its maker at any time or by anyone once it has expired
+		#[pallet::call_index(9)]
+		#[pallet::weight(Weight::default())]
+		pub fn withdraw_offer(origin: OriginFor<T>, offer_id: OfferId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = Offers::<T>::take(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+
+			ensure!(
+				who == offer.buyer || frame_system::Pallet::<T>::block_number() >= offer.expiry,
+				Error::<T>::OfferNotExpired
+			);
+
+			T::Currency::unreserve(&offer.buyer, Self::total_price(offer.price, offer.amount)?);
+
+			Self::deposit_event(Event::<T>::OfferWithdrawn(offer_id));
+
+			Ok(())
+		}
+
+		/// List a resource for sale at a fixed price, moving the listed amount to the pallet
+		/// account until it is bought or the sale is cancelled
+		#[pallet::call_index(10)]
+		#[pallet::weight(Weight::default())]
+		pub fn set_escrow_sale(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			price: BalanceOf<T>,
+			amount: u128,
+			asset: AssetKind<AssetIdOf<T>>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Self::list(
+				nft_id,
+				origin.clone(),
+				SaleData {
+					price,
+					amount,
+					pricing: Pricing::Fixed,
+					asset: asset.clone(),
+					custody: Custody::Escrowed,
+				},
+			)?;
+
+			Self::deposit_event(Event::<T>::ListedForEscrowSale(
+				nft_id, origin, price, amount, asset,
+			));
+
+			Ok(())
+		}
+
+		/// Buy from several sellers at once, failing as a whole if any purchase fails or if more
+		/// than `max_total_price` would be paid. All the listings must be priced in the same asset.
+		#[pallet::call_index(11)]
+		#[pallet::weight(Weight::default())]
+		pub fn buy_many(
+			origin: OriginFor<T>,
+			nft_id: T::ResourceId,
+			max_total_price: BalanceOf<T>,
+			purchases: BoundedVec<(T::AccountId, u128), T::MaxPurchases>,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+
+			let mut total_paid = BalanceOf::<T>::zero();
+			let mut paid_in = None;
+
+			for (seller, amount) in purchases {
+				let (paid, asset) = Self::do_buy(nft_id, seller, buyer.clone(), amount)?;
+
+				if let Some(paid_in) = &paid_in {
+					ensure!(*paid_in == asset, Error::<T>::AssetMismatch);
+				}
+				paid_in = Some(asset);
+
+				total_paid = total_paid.checked_add(&paid).ok_or(Error::<T>::Overflow)?;
+			}
+
+			// Calls are transactional, so every purchase is reverted if the cap is exceeded
+			ensure!(total_paid <= max_total_price, Error::<T>::PriceCapExceeded);
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the resources of escrowed sales
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// Reserve or escrow the listed amount and store the sale, replacing any previous sale by
+	/// `seller`.
+	fn list(nft_id: T::ResourceId, seller: T::AccountId, sale_data: SaleData<T>) -> DispatchResult {
+		ensure!(sale_data.amount > 0, Error::<T>::ZeroAmount);
+
+		// Release what was listed before, so the new listing can reuse it
+		if let Some(listed) = ResourcesForSale::<T>::get(nft_id, seller.clone()) {
+			Self::release(nft_id, seller.clone(), &listed)?;
+			Self::unindex_ask(nft_id, &listed.asset, &seller);
+		}
+
+		match sale_data.custody {
+			Custody::Reserved => T::Resource::reserve(nft_id, seller.clone(), sale_data.amount),
+			Custody::Escrowed => T::Resource::transfer(
+				nft_id,
+				seller.clone(),
+				Self::account_id(),
+				sale_data.amount,
+				Precision::Exact,
+			)
+			.map(|_| ()),
+		}
+		.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+		if let Pricing::Fixed = sale_data.pricing {
+			Self::index_ask(
+				nft_id,
+				sale_data.asset.clone(),
+				seller.clone(),
+				sale_data.price,
+			);
+		}
+
+		ResourcesForSale::<T>::insert(nft_id, seller, sale_data);
+
+		Ok(())
+	}
+
+	/// Buy `amount` of the resource listed by `seller`, returning the total price paid and the
+	/// asset it was paid in
+	fn do_buy(
+		nft_id: T::ResourceId,
+		seller: T::AccountId,
+		buyer: T::AccountId,
+		amount: u128,
+	) -> Result<(BalanceOf<T>, AssetKind<AssetIdOf<T>>), DispatchError> {
+		let sale_data =
+			ResourcesForSale::<T>::get(nft_id, seller.clone()).ok_or(Error::<T>::SaleNotFound)?;
+
+		ensure!(amount <= sale_data.amount, Error::<T>::NotEnoughInSale);
+		if let Custody::Reserved = sale_data.custody {
+			let owned = T::Resource::amount_owned(nft_id, seller.clone());
 			ensure!(sale_data.amount <= owned, Error::<T>::NotEnoughOwned);
+		}
+
+		let price = sale_data.price_at(frame_system::Pallet::<T>::block_number());
+		let total_to_pay = Self::total_price(price, amount)?;
 
-			let total_to_pay = sale_data
-				.price
-				.checked_mul(&amount.checked_into().ok_or(Error::<T>::Overflow)?)
-				.ok_or(Error::<T>::Overflow)?;
+		let fee = T::MarketplaceFee::get() * total_to_pay;
+		let paid_to_seller = total_to_pay - fee;
 
-			T::Currency::transfer(&buyer, &seller, total_to_pay, KeepAlive)?;
+		let asset = sale_data.asset.clone();
+		Self::pay(asset.clone(), &buyer, &seller, paid_to_seller)?;
+		if !fee.is_zero() {
+			Self::pay(asset.clone(), &buyer, &T::FeeDestination::get(), fee)?;
+		}
+
+		// Calls are transactional, so the payment is reverted if the resource is not delivered
+		match sale_data.custody {
+			Custody::Reserved => T::Resource::transfer_reserved(
+				nft_id,
+				seller.clone(),
+				buyer.clone(),
+				amount,
+				Precision::Exact,
+			),
+			Custody::Escrowed => T::Resource::transfer(
+				nft_id,
+				Self::account_id(),
+				buyer.clone(),
+				amount,
+				Precision::Exact,
+			),
+		}
+		.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+		if amount == sale_data.amount {
+			ResourcesForSale::<T>::remove(nft_id, seller.clone());
+			Self::unindex_ask(nft_id, &asset, &seller);
+		} else {
+			ResourcesForSale::<T>::insert(
+				nft_id,
+				seller.clone(),
+				SaleData {
+					amount: sale_data.amount - amount,
+					..sale_data
+				},
+			);
+		}
+
+		Self::deposit_event(Event::<T>::Sold(
+			nft_id,
+			seller,
+			buyer,
+			amount,
+			paid_to_seller,
+			fee,
+		));
+
+		Ok((total_to_pay, asset))
+	}
+
+	/// Add a fixed price listing to the index, after the listings at the same or a lower price.
+	/// If the index is full, the priciest listing is dropped from it, which is the new one when
+	/// nothing is cheaper.
+	fn index_ask(
+		nft_id: T::ResourceId,
+		asset: AssetKind<AssetIdOf<T>>,
+		seller: T::AccountId,
+		price: BalanceOf<T>,
+	) {
+		AskIndex::<T>::mutate(nft_id, asset, |asks| {
+			let position = asks.partition_point(|(ask_price, _)| *ask_price <= price);
+			let _ = asks.force_insert_keep_left(position, (price, seller));
+		});
+	}
 
-			T::Resource::transfer(nft_id, seller.clone(), buyer.clone(), amount);
+	/// Remove the listing of `seller` from the index, if any. A full index is then refilled with
+	/// the cheapest fixed price listing left out of it.
+	fn unindex_ask(nft_id: T::ResourceId, asset: &AssetKind<AssetIdOf<T>>, seller: &T::AccountId) {
+		AskIndex::<T>::mutate(nft_id, asset, |asks| {
+			let was_full = asks.is_full();
+			asks.retain(|(_, ask_seller)| ask_seller != seller);
+			if !was_full || asks.is_full() {
+				return
+			}
+
+			// The listing of `seller` may still be stored, while it is being replaced
+			let left_out = ResourcesForSale::<T>::iter_prefix(nft_id)
+				.filter(|(listing_seller, sale_data)| {
+					matches!(sale_data.pricing, Pricing::Fixed)
+						&& sale_data.asset == *asset
+						&& listing_seller != seller
+						&& !asks.iter().any(|(_, ask_seller)| ask_seller == listing_seller)
+				})
+				.min_by_key(|(_, sale_data)| sale_data.price);
+			if let Some((listing_seller, sale_data)) = left_out {
+				let position = asks.partition_point(|(ask_price, _)| *ask_price <= sale_data.price);
+				let _ = asks.try_insert(position, (sale_data.price, listing_seller));
+			}
+		});
+	}
 
-			if amount == sale_data.amount {
-				ResourcesForSale::<T>::remove(nft_id, seller.clone());
+	/// The cheapest fixed price listing of `nft_id` in `asset`
+	pub fn best_ask(nft_id: T::ResourceId, asset: AssetKind<AssetIdOf<T>>) -> Option<AskOf<T>> {
+		let (price, seller) = AskIndex::<T>::get(nft_id, &asset).into_iter().next()?;
+		let sale_data = ResourcesForSale::<T>::get(nft_id, &seller)?;
+
+		Some(Ask {
+			seller,
+			price,
+			amount: sale_data.amount,
+			asset,
+		})
+	}
+
+	/// All the listings of `nft_id` in `asset`, at their current price, cheapest first
+	pub fn asks(nft_id: T::ResourceId, asset: AssetKind<AssetIdOf<T>>) -> Vec<AskOf<T>> {
+		let now = frame_system::Pallet::<T>::block_number();
+
+		let mut asks: Vec<AskOf<T>> = ResourcesForSale::<T>::iter_prefix(nft_id)
+			.filter(|(_, sale_data)| sale_data.asset == asset)
+			.map(|(seller, sale_data)| Ask {
+				seller,
+				price: sale_data.price_at(now),
+				amount: sale_data.amount,
+				asset: sale_data.asset,
+			})
+			.collect();
+		asks.sort_by(|a, b| a.price.cmp(&b.price));
+
+		asks
+	}
+
+	/// Give back to `seller` the resource held for a sale
+	fn release(
+		nft_id: T::ResourceId,
+		seller: T::AccountId,
+		sale_data: &SaleData<T>,
+	) -> DispatchResult {
+		match sale_data.custody {
+			Custody::Reserved => {
+				T::Resource::unreserve(nft_id, seller, sale_data.amount);
+			},
+			Custody::Escrowed => {
+				T::Resource::transfer(
+					nft_id,
+					Self::account_id(),
+					seller,
+					sale_data.amount,
+					Precision::Exact,
+				)?;
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Transfer `amount` of `asset` from `from` to `to`
+	fn pay(
+		asset: AssetKind<AssetIdOf<T>>,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		match asset {
+			AssetKind::Native => T::Currency::transfer(from, to, amount, KeepAlive),
+			AssetKind::Asset(asset_id) => {
+				<T::Assets as fungibles::Mutate<_>>::transfer(
+					asset_id,
+					from,
+					to,
+					amount,
+					Preservation::Preserve,
+				)?;
+				Ok(())
+			},
+		}
+	}
+
+	/// The price of `amount` units at `price` each
+	fn total_price(price: BalanceOf<T>, amount: u128) -> Result<BalanceOf<T>, Error<T>> {
+		price
+			.checked_mul(&amount.checked_into().ok_or(Error::<T>::Overflow)?)
+			.ok_or(Error::<T>::Overflow)
+	}
+
+	/// Hand the resource of an ended auction to the best bidder and pay the seller.
+	/// Returns the weight consumed.
+	fn settle_auction(auction_id: AuctionId) -> Weight {
+		let Some(auction) = Auctions::<T>::take(auction_id) else {
+			return T::DbWeight::get().reads(1)
+		};
+
+		let Some((winner, price)) = auction.best_bid else {
+			T::Resource::unreserve(auction.resource_id, auction.seller, auction.amount);
+			Self::deposit_event(Event::<T>::AuctionUnsold(auction_id));
+
+			return T::DbWeight::get().reads_writes(2, 2)
+		};
+
+		// Hooks are not transactional: the resource is only delivered if the seller is paid
+		let settled = storage::with_transaction(|| {
+			let result = Self::deliver_auction(&auction, &winner, price);
+			if result.is_ok() {
+				TransactionOutcome::Commit(result)
 			} else {
-				ResourcesForSale::<T>::mutate(nft_id, seller.clone(), |data| data.amount -= amount);
+				TransactionOutcome::Rollback(result)
 			}
+		});
+		match settled {
+			Ok((paid_to_seller, fee)) => {
+				Self::deposit_event(Event::<T>::AuctionSettled(
+					auction_id,
+					winner,
+					paid_to_seller,
+					fee,
+				));
+			},
+			Err(_) => {
+				T::Currency::unreserve(&winner, price);
+				T::Resource::unreserve(auction.resource_id, auction.seller, auction.amount);
+				Self::deposit_event(Event::<T>::AuctionFailed(auction_id));
+			},
+		}
 
-			Self::deposit_event(Event::<T>::Sold(nft_id, seller, buyer, amount));
+		// auction, resource balances and reserve of both parties, currency accounts of both parties
+		// and of the fee destination
+		T::DbWeight::get().reads_writes(7, 7)
+	}
 
-			Ok(())
+	/// Hand the resource of an auction to `winner` and pay the seller out of the winning bid,
+	/// returning the amount paid to the seller and the marketplace fee
+	fn deliver_auction(
+		auction: &AuctionData<T>,
+		winner: &T::AccountId,
+		price: BalanceOf<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		T::Resource::transfer_reserved(
+			auction.resource_id,
+			auction.seller.clone(),
+			winner.clone(),
+			auction.amount,
+			Precision::Exact,
+		)?;
+
+		Self::pay_reserved(winner, &auction.seller, price, Error::<T>::BidNotFunded)
+	}
+
+	/// Pay `total` out of the reserve of `from`, minus the marketplace fee which goes to the fee
+	/// destination. Returns the amount paid to `to` and the fee.
+	fn pay_reserved(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		total: BalanceOf<T>,
+		not_funded: Error<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		let fee = T::MarketplaceFee::get() * total;
+		let paid_to_seller = total - fee;
+
+		let mut not_paid =
+			T::Currency::repatriate_reserved(from, to, paid_to_seller, BalanceStatus::Free)?;
+		if !fee.is_zero() {
+			not_paid += T::Currency::repatriate_reserved(
+				from,
+				&T::FeeDestination::get(),
+				fee,
+				BalanceStatus::Free,
+			)?;
 		}
+		ensure!(not_paid.is_zero(), not_funded);
+
+		Ok((paid_to_seller, fee))
 	}
 }
@@ -0,0 +1,20 @@
+//! Runtime API to query the listings of the marketplace.
+
+use crate::types::Ask;
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait MarketplaceApi<ResourceId, AccountId, AssetKind, Balance>
+	where
+		ResourceId: Codec,
+		AccountId: Codec,
+		AssetKind: Codec,
+		Balance: Codec,
+	{
+		/// The cheapest fixed price listing of `resource_id` in `asset`
+		fn best_ask(resource_id: ResourceId, asset: AssetKind) -> Option<Ask<AccountId, AssetKind, Balance>>;
+		/// All the listings of `resource_id` in `asset`, at their current price, cheapest first
+		fn asks(resource_id: ResourceId, asset: AssetKind) -> Vec<Ask<AccountId, AssetKind, Balance>>;
+	}
+}
@@ -1,5 +1,9 @@
-use crate::{tests::mock::*, Error};
-use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+use crate::{
+	tests::mock::*,
+	types::{Ask, AssetKind, Custody},
+	Error,
+};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin, traits::OnInitialize};
 
 fn create_nft(amount: u128) {
 	let metadata = "Some metadata".as_bytes().to_vec().try_into().unwrap();
@@ -12,6 +16,12 @@ fn amount_owned(resource_id: u128, address: u64) -> u128 {
 	<TestRuntime as crate::pallet::Config>::Resource::amount_owned(resource_id, address)
 }
 
+fn amount_reserved(resource_id: u128, address: u64) -> u128 {
+	use pallet_marketplace_nfts::types::Sellable;
+
+	<TestRuntime as crate::pallet::Config>::Resource::amount_reserved(resource_id, address)
+}
+
 mod set_sale {
 	use super::*;
 
@@ -26,12 +36,40 @@ mod set_sale {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				price,
-				amount
+				amount,
+				AssetKind::Native
 			));
 
-			let sale = Marketplace::resource_for_sale(0, ALICE);
+			let sale = Marketplace::resource_for_sale(0, ALICE).unwrap();
 			assert_eq!(sale.price, price);
 			assert_eq!(sale.amount, amount);
+			assert_eq!(amount_reserved(0, ALICE), amount);
+		})
+	}
+
+	#[test]
+	fn relist() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				4,
+				AssetKind::Native
+			));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				500,
+				5,
+				AssetKind::Native
+			));
+
+			let sale = Marketplace::resource_for_sale(0, ALICE).unwrap();
+			assert_eq!(sale.price, 500);
+			assert_eq!(sale.amount, 5);
+			assert_eq!(amount_reserved(0, ALICE), 5);
 		})
 	}
 
@@ -39,7 +77,7 @@ mod set_sale {
 	fn nft_does_not_exist() {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 2),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 2, AssetKind::Native),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
 		})
@@ -50,7 +88,7 @@ mod set_sale {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			create_nft(5);
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 0),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 0, AssetKind::Native),
 				Error::<TestRuntime>::ZeroAmount
 			);
 		})
@@ -61,7 +99,7 @@ mod set_sale {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			create_nft(5);
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 10),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 10, AssetKind::Native),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
 		})
@@ -71,7 +109,7 @@ mod set_sale {
 	fn must_be_signed() {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::none(), 0, 1000, 2),
+				Marketplace::set_sale(RuntimeOrigin::none(), 0, 1000, 2, AssetKind::Native),
 				BadOrigin
 			);
 		})
@@ -91,15 +129,21 @@ mod buy {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				price,
-				2
+				2,
+				AssetKind::Native
 			));
 
 			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
 
+			let fee = price / 10;
 			assert_eq!(bob_funds - price, Balances::free_balance(BOB));
-			assert_eq!(price, Balances::free_balance(ALICE));
+			assert_eq!(price - fee, Balances::free_balance(ALICE));
+			assert_eq!(fee, Balances::free_balance(TREASURY));
 			assert_eq!(amount_owned(0, ALICE), 4);
 			assert_eq!(amount_owned(0, BOB), 1);
+			System::assert_last_event(
+				crate::Event::Sold(0, ALICE, BOB, 1, price - fee, fee).into(),
+			);
 		})
 	}
 
@@ -113,7 +157,8 @@ mod buy {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				price,
-				2
+				2,
+				AssetKind::Native
 			));
 
 			let amount_buy = 2;
@@ -125,13 +170,86 @@ mod buy {
 				amount_buy
 			));
 
+			let fee = total_price / 10;
 			assert_eq!(bob_funds - total_price, Balances::free_balance(BOB));
-			assert_eq!(total_price, Balances::free_balance(ALICE));
+			assert_eq!(total_price - fee, Balances::free_balance(ALICE));
+			assert_eq!(fee, Balances::free_balance(TREASURY));
 			assert_eq!(amount_owned(0, ALICE), 3);
 			assert_eq!(amount_owned(0, BOB), 2);
 		})
 	}
 
+	#[test]
+	fn ok_in_asset() {
+		let stablecoin = 7;
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_ok!(Assets::force_create(
+				RuntimeOrigin::root(),
+				stablecoin.into(),
+				ALICE,
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				RuntimeOrigin::signed(ALICE),
+				stablecoin.into(),
+				BOB,
+				2000
+			));
+
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Asset(stablecoin)
+			));
+
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+			assert_eq!(Assets::balance(stablecoin, BOB), 1000);
+			assert_eq!(Assets::balance(stablecoin, ALICE), 900);
+			assert_eq!(Assets::balance(stablecoin, TREASURY), 100);
+			assert_eq!(Balances::free_balance(ALICE), 0);
+			assert_eq!(amount_owned(0, BOB), 1);
+		})
+	}
+
+	#[test]
+	fn not_enough_asset() {
+		let stablecoin = 7;
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_ok!(Assets::force_create(
+				RuntimeOrigin::root(),
+				stablecoin.into(),
+				ALICE,
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				RuntimeOrigin::signed(ALICE),
+				stablecoin.into(),
+				BOB,
+				500
+			));
+
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Asset(stablecoin)
+			));
+
+			assert!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1).is_err());
+			assert_eq!(Assets::balance(stablecoin, BOB), 500);
+			assert_eq!(Balances::free_balance(BOB), 2000);
+			assert_eq!(amount_owned(0, BOB), 0);
+		})
+	}
+
 	#[test]
 	fn not_enough_in_sale() {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
@@ -140,7 +258,8 @@ mod buy {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				1000,
-				2
+				2,
+				AssetKind::Native
 			));
 
 			assert_noop!(
@@ -151,22 +270,138 @@ mod buy {
 	}
 
 	#[test]
-	fn not_enough_owned() {
-		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+	fn listed_amount_is_reserved() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
 			create_nft(5);
 			assert_ok!(Marketplace::set_sale(
 				RuntimeOrigin::signed(ALICE),
 				0,
 				1000,
-				2
+				2,
+				AssetKind::Native
 			));
 
+			// Only the unlisted part can be moved away
 			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 4, 0));
+			assert_eq!(amount_owned(0, ALICE), 2);
+
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 2));
+
+			assert_eq!(amount_owned(0, ALICE), 0);
+			assert_eq!(amount_owned(0, BOB), 2);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn payment_reverted_when_not_delivered() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			use pallet_marketplace_nfts::types::Sellable;
+
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			// The listed amount is not reserved anymore
+			<NFTs as Sellable<u64, u128>>::unreserve(0, ALICE, 2);
 
 			assert_noop!(
 				Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 2),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
+			assert_eq!(bob_funds, Balances::free_balance(BOB));
+			assert_eq!(0, Balances::free_balance(TREASURY));
+		})
+	}
+
+	#[test]
+	fn fee_below_existential_deposit() {
+		let bob_funds = 2000;
+		ExtBuilder::default()
+			.existential_deposit(500)
+			.balances(vec![(BOB, bob_funds), (TREASURY, 500)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				let price = 1000;
+				assert_ok!(Marketplace::set_sale(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					price,
+					2,
+					AssetKind::Native
+				));
+
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+				let fee = price / 10;
+				assert_eq!(price - fee, Balances::free_balance(ALICE));
+				assert_eq!(500 + fee, Balances::free_balance(TREASURY));
+			})
+	}
+
+	#[test]
+	fn fee_destination_must_exist() {
+		let bob_funds = 2000;
+		ExtBuilder::default()
+			.existential_deposit(500)
+			.balances(vec![(BOB, bob_funds)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				assert_ok!(Marketplace::set_sale(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					1000,
+					2,
+					AssetKind::Native
+				));
+
+				assert_noop!(
+					Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1),
+					pallet_balances::Error::<TestRuntime>::ExistentialDeposit
+				);
+				assert_eq!(bob_funds, Balances::free_balance(BOB));
+			})
+	}
+
+	#[test]
+	fn sale_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1),
+				Error::<TestRuntime>::SaleNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn sold_out() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				1,
+				AssetKind::Native
+			));
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+			assert!(Marketplace::resource_for_sale(0, ALICE).is_none());
+			assert_noop!(
+				Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1),
+				Error::<TestRuntime>::SaleNotFound
+			);
 		})
 	}
 
@@ -180,3 +415,1280 @@ mod buy {
 		})
 	}
 }
+
+mod cancel_sale {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			assert_ok!(Marketplace::cancel_sale(RuntimeOrigin::signed(ALICE), 0));
+
+			assert!(Marketplace::resource_for_sale(0, ALICE).is_none());
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			System::assert_last_event(crate::Event::SaleCancelled(0, ALICE).into());
+		})
+	}
+
+	#[test]
+	fn sale_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::cancel_sale(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::SaleNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::cancel_sale(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod update_price {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			assert_ok!(Marketplace::update_price(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				500
+			));
+
+			let sale = Marketplace::resource_for_sale(0, ALICE).unwrap();
+			assert_eq!(sale.price, 500);
+			assert_eq!(sale.amount, 2);
+			System::assert_last_event(crate::Event::PriceUpdated(0, ALICE, 500).into());
+		})
+	}
+
+	#[test]
+	fn sale_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::update_price(RuntimeOrigin::signed(ALICE), 0, 500),
+				Error::<TestRuntime>::SaleNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::update_price(RuntimeOrigin::none(), 0, 500),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod set_dutch_sale {
+	use super::*;
+	use crate::types::Pricing;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+
+			let sale = Marketplace::resource_for_sale(0, ALICE).unwrap();
+			assert_eq!(sale.price, 1000);
+			assert_eq!(sale.amount, 2);
+			assert_eq!(
+				sale.pricing,
+				Pricing::Dutch {
+					floor_price: 400,
+					decay_per_block: 100,
+					start_block: 1
+				}
+			);
+			assert_eq!(amount_reserved(0, ALICE), 2);
+			System::assert_last_event(
+				crate::Event::ListedForDutchSale(0, ALICE, 1000, 400, 100, 2, AssetKind::Native)
+					.into(),
+			);
+		})
+	}
+
+	#[test]
+	fn floor_above_start_price() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::set_dutch_sale(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					1000,
+					1001,
+					100,
+					2,
+					AssetKind::Native
+				),
+				Error::<TestRuntime>::FloorAboveStartPrice
+			);
+		})
+	}
+
+	#[test]
+	fn price_cannot_be_updated() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+			assert_noop!(
+				Marketplace::update_price(RuntimeOrigin::signed(ALICE), 0, 500),
+				Error::<TestRuntime>::NotFixedPrice
+			);
+		})
+	}
+
+	#[test]
+	fn buy_at_decayed_price() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+
+			System::set_block_number(4);
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 2));
+
+			assert_eq!(Balances::free_balance(BOB), bob_funds - 2 * 700);
+			assert_eq!(Balances::free_balance(ALICE), 2 * 700 - 2 * 70);
+			assert_eq!(amount_owned(0, BOB), 2);
+		})
+	}
+
+	#[test]
+	fn buy_at_floor_price() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+
+			System::set_block_number(100);
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+			assert_eq!(Balances::free_balance(BOB), bob_funds - 400);
+			assert_eq!(Balances::free_balance(ALICE), 400 - 40);
+		})
+	}
+}
+
+mod create_auction {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::create_auction(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				2,
+				1000,
+				10
+			));
+
+			let auction = Marketplace::auction(0).unwrap();
+			assert_eq!(auction.resource_id, 0);
+			assert_eq!(auction.seller, ALICE);
+			assert_eq!(auction.amount, 2);
+			assert_eq!(auction.reserve_price, 1000);
+			assert_eq!(auction.end_block, 10);
+			assert_eq!(auction.best_bid, None);
+			assert_eq!(Marketplace::next_auction_id(), 1);
+			assert_eq!(Marketplace::auctions_ending_at(10).into_inner(), vec![0]);
+			assert_eq!(amount_reserved(0, ALICE), 2);
+			System::assert_last_event(
+				crate::Event::AuctionCreated(0, 0, ALICE, 2, 1000, 10).into(),
+			);
+		})
+	}
+
+	#[test]
+	fn zero_amount() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::create_auction(RuntimeOrigin::signed(ALICE), 0, 0, 1000, 10),
+				Error::<TestRuntime>::ZeroAmount
+			);
+		})
+	}
+
+	#[test]
+	fn end_block_in_the_past() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::create_auction(RuntimeOrigin::signed(ALICE), 0, 2, 1000, 1),
+				Error::<TestRuntime>::EndBlockInThePast
+			);
+		})
+	}
+
+	#[test]
+	fn not_enough_owned() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				4,
+				AssetKind::Native
+			));
+			assert_noop!(
+				Marketplace::create_auction(RuntimeOrigin::signed(ALICE), 0, 2, 1000, 10),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn too_many_auctions_in_block() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::create_auction(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1,
+				1000,
+				10
+			));
+			assert_ok!(Marketplace::create_auction(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1,
+				1000,
+				10
+			));
+			assert_noop!(
+				Marketplace::create_auction(RuntimeOrigin::signed(ALICE), 0, 1, 1000, 10),
+				Error::<TestRuntime>::TooManyAuctionsInBlock
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::create_auction(RuntimeOrigin::none(), 0, 2, 1000, 10),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod bid {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	fn create_auction() {
+		create_nft(5);
+		assert_ok!(Marketplace::create_auction(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			2,
+			1000,
+			10
+		));
+	}
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_auction();
+			assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+
+			assert_eq!(Marketplace::auction(0).unwrap().best_bid, Some((BOB, 1500)));
+			assert_eq!(Balances::reserved_balance(BOB), 1500);
+			System::assert_last_event(crate::Event::BidPlaced(0, BOB, 1500).into());
+		})
+	}
+
+	#[test]
+	fn outbid() {
+		ExtBuilder::default()
+			.balances(vec![(BOB, 2000), (CHARLIE, 2000)])
+			.build()
+			.execute_with(|| {
+				create_auction();
+				assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+				assert_ok!(Marketplace::bid(RuntimeOrigin::signed(CHARLIE), 0, 1600));
+
+				assert_eq!(
+					Marketplace::auction(0).unwrap().best_bid,
+					Some((CHARLIE, 1600))
+				);
+				assert_eq!(Balances::reserved_balance(BOB), 0);
+				assert_eq!(Balances::reserved_balance(CHARLIE), 1600);
+			})
+	}
+
+	#[test]
+	fn below_reserve_price() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_auction();
+			assert_noop!(
+				Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 999),
+				Error::<TestRuntime>::BidTooLow
+			);
+		})
+	}
+
+	#[test]
+	fn not_above_best_bid() {
+		ExtBuilder::default()
+			.balances(vec![(BOB, 2000), (CHARLIE, 2000)])
+			.build()
+			.execute_with(|| {
+				create_auction();
+				assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+				assert_noop!(
+					Marketplace::bid(RuntimeOrigin::signed(CHARLIE), 0, 1500),
+					Error::<TestRuntime>::BidTooLow
+				);
+			})
+	}
+
+	#[test]
+	fn not_enough_funds() {
+		ExtBuilder::default().balances(vec![(BOB, 500)]).build().execute_with(|| {
+			create_auction();
+			assert!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1000).is_err());
+		})
+	}
+
+	#[test]
+	fn auction_ended() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_auction();
+			System::set_block_number(10);
+			assert_noop!(
+				Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500),
+				Error::<TestRuntime>::AuctionEnded
+			);
+		})
+	}
+
+	#[test]
+	fn own_auction() {
+		ExtBuilder::default().balances(vec![(ALICE, 2000)]).build().execute_with(|| {
+			create_auction();
+			assert_noop!(
+				Marketplace::bid(RuntimeOrigin::signed(ALICE), 0, 1500),
+				Error::<TestRuntime>::BidOnOwnAuction
+			);
+		})
+	}
+
+	#[test]
+	fn auction_not_found() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500),
+				Error::<TestRuntime>::AuctionNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(Marketplace::bid(RuntimeOrigin::none(), 0, 1500), BadOrigin);
+		})
+	}
+}
+
+mod settle_auction {
+	use super::*;
+
+	fn create_auction() {
+		create_nft(5);
+		assert_ok!(Marketplace::create_auction(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			2,
+			1000,
+			10
+		));
+	}
+
+	#[test]
+	fn ok() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_auction();
+			assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+
+			System::set_block_number(10);
+			<Marketplace as OnInitialize<u64>>::on_initialize(10);
+
+			assert!(Marketplace::auction(0).is_none());
+			assert!(Marketplace::auctions_ending_at(10).is_empty());
+			assert_eq!(amount_owned(0, ALICE), 3);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(amount_owned(0, BOB), 2);
+			assert_eq!(Balances::free_balance(ALICE), 1350);
+			assert_eq!(Balances::free_balance(TREASURY), 150);
+			assert_eq!(Balances::free_balance(BOB), bob_funds - 1500);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			System::assert_last_event(crate::Event::AuctionSettled(0, BOB, 1350, 150).into());
+		})
+	}
+
+	#[test]
+	fn unsold() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_auction();
+
+			System::set_block_number(10);
+			<Marketplace as OnInitialize<u64>>::on_initialize(10);
+
+			assert!(Marketplace::auction(0).is_none());
+			assert_eq!(amount_owned(0, ALICE), 5);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			System::assert_last_event(crate::Event::AuctionUnsold(0).into());
+		})
+	}
+
+	#[test]
+	fn failed() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			use pallet_marketplace_nfts::types::Sellable;
+
+			create_auction();
+			assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+
+			// The auctioned amount is not reserved anymore
+			<NFTs as Sellable<u64, u128>>::unreserve(0, ALICE, 2);
+
+			System::set_block_number(10);
+			<Marketplace as OnInitialize<u64>>::on_initialize(10);
+
+			assert!(Marketplace::auction(0).is_none());
+			assert_eq!(amount_owned(0, ALICE), 5);
+			assert_eq!(Balances::free_balance(BOB), bob_funds);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			System::assert_last_event(crate::Event::AuctionFailed(0).into());
+		})
+	}
+
+	#[test]
+	fn failed_payment() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			use frame_support::traits::ReservableCurrency;
+
+			create_auction();
+			assert_ok!(Marketplace::bid(RuntimeOrigin::signed(BOB), 0, 1500));
+
+			// Part of the bid is not reserved anymore
+			Balances::unreserve(&BOB, 1000);
+
+			System::set_block_number(10);
+			<Marketplace as OnInitialize<u64>>::on_initialize(10);
+
+			assert!(Marketplace::auction(0).is_none());
+			assert_eq!(amount_owned(0, ALICE), 5);
+			assert_eq!(amount_owned(0, BOB), 0);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(Balances::free_balance(ALICE), 0);
+			assert_eq!(Balances::free_balance(BOB), bob_funds);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			System::assert_last_event(crate::Event::AuctionFailed(0).into());
+		})
+	}
+
+	#[test]
+	fn only_ended_auctions() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_auction();
+
+			System::set_block_number(9);
+			<Marketplace as OnInitialize<u64>>::on_initialize(9);
+
+			assert!(Marketplace::auction(0).is_some());
+			assert_eq!(amount_reserved(0, ALICE), 2);
+		})
+	}
+}
+
+mod make_offer {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+
+			let offer = Marketplace::offer(0).unwrap();
+			assert_eq!(offer.buyer, BOB);
+			assert_eq!(offer.seller, None);
+			assert_eq!(offer.price, 300);
+			assert_eq!(offer.amount, 2);
+			assert_eq!(Marketplace::next_offer_id(), 1);
+			assert_eq!(Balances::reserved_balance(BOB), 600);
+			System::assert_last_event(crate::Event::OfferMade(0, 0, BOB, None, 300, 2, 10).into());
+		})
+	}
+
+	#[test]
+	fn zero_amount() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::make_offer(RuntimeOrigin::signed(BOB), 0, None, 300, 0, 10),
+				Error::<TestRuntime>::ZeroAmount
+			);
+		})
+	}
+
+	#[test]
+	fn expiry_in_the_past() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			System::set_block_number(10);
+			assert_noop!(
+				Marketplace::make_offer(RuntimeOrigin::signed(BOB), 0, None, 300, 2, 10),
+				Error::<TestRuntime>::OfferExpired
+			);
+		})
+	}
+
+	#[test]
+	fn not_enough_funds() {
+		ExtBuilder::default().balances(vec![(BOB, 500)]).build().execute_with(|| {
+			assert!(
+				Marketplace::make_offer(RuntimeOrigin::signed(BOB), 0, None, 300, 2, 10).is_err()
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::make_offer(RuntimeOrigin::none(), 0, None, 300, 2, 10),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod accept_offer {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	#[test]
+	fn ok() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			assert_ok!(Marketplace::accept_offer(RuntimeOrigin::signed(ALICE), 0));
+
+			assert!(Marketplace::offer(0).is_none());
+			assert_eq!(amount_owned(0, ALICE), 3);
+			assert_eq!(amount_owned(0, BOB), 2);
+			assert_eq!(Balances::free_balance(ALICE), 540);
+			assert_eq!(Balances::free_balance(TREASURY), 60);
+			assert_eq!(Balances::free_balance(BOB), bob_funds - 600);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			System::assert_last_event(crate::Event::OfferAccepted(0, ALICE, BOB, 540, 60).into());
+		})
+	}
+
+	#[test]
+	fn addressed_to_seller() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				Some(CHARLIE),
+				300,
+				2,
+				10
+			));
+			assert_noop!(
+				Marketplace::accept_offer(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::NotOfferRecipient
+			);
+		})
+	}
+
+	#[test]
+	fn listed_amount_cannot_fill_offer() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				4,
+				AssetKind::Native
+			));
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			assert_noop!(
+				Marketplace::accept_offer(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn expired() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			System::set_block_number(10);
+			assert_noop!(
+				Marketplace::accept_offer(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::OfferExpired
+			);
+		})
+	}
+
+	#[test]
+	fn offer_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::accept_offer(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::OfferNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::accept_offer(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod withdraw_offer {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			assert_ok!(Marketplace::withdraw_offer(RuntimeOrigin::signed(BOB), 0));
+
+			assert!(Marketplace::offer(0).is_none());
+			assert_eq!(Balances::free_balance(BOB), 2000);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			System::assert_last_event(crate::Event::OfferWithdrawn(0).into());
+		})
+	}
+
+	#[test]
+	fn by_anyone_once_expired() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			System::set_block_number(10);
+			assert_ok!(Marketplace::withdraw_offer(RuntimeOrigin::signed(ALICE), 0));
+
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+		})
+	}
+
+	#[test]
+	fn not_expired() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			assert_ok!(Marketplace::make_offer(
+				RuntimeOrigin::signed(BOB),
+				0,
+				None,
+				300,
+				2,
+				10
+			));
+			assert_noop!(
+				Marketplace::withdraw_offer(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::OfferNotExpired
+			);
+		})
+	}
+
+	#[test]
+	fn offer_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::withdraw_offer(RuntimeOrigin::signed(BOB), 0),
+				Error::<TestRuntime>::OfferNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::withdraw_offer(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod set_escrow_sale {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_escrow_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			let sale = Marketplace::resource_for_sale(0, ALICE).unwrap();
+			assert_eq!(sale.custody, Custody::Escrowed);
+			assert_eq!(amount_owned(0, ALICE), 3);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(amount_owned(0, Marketplace::account_id()), 2);
+			System::assert_last_event(
+				crate::Event::ListedForEscrowSale(0, ALICE, 1000, 2, AssetKind::Native).into(),
+			);
+		})
+	}
+
+	#[test]
+	fn buy() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_escrow_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			// The seller cannot take back what is in escrow
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 3, 0));
+			assert_eq!(amount_owned(0, ALICE), 0);
+
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+			assert_eq!(amount_owned(0, BOB), 1);
+			assert_eq!(amount_owned(0, Marketplace::account_id()), 1);
+			assert_eq!(Balances::free_balance(ALICE), 900);
+			assert_eq!(Marketplace::resource_for_sale(0, ALICE).unwrap().amount, 1);
+		})
+	}
+
+	#[test]
+	fn cancel() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_escrow_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+
+			assert_ok!(Marketplace::cancel_sale(RuntimeOrigin::signed(ALICE), 0));
+
+			assert!(Marketplace::resource_for_sale(0, ALICE).is_none());
+			assert_eq!(amount_owned(0, ALICE), 5);
+			assert_eq!(amount_owned(0, Marketplace::account_id()), 0);
+		})
+	}
+
+	#[test]
+	fn relist_with_reservation() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_escrow_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				AssetKind::Native
+			));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				5,
+				AssetKind::Native
+			));
+
+			assert_eq!(amount_owned(0, ALICE), 5);
+			assert_eq!(amount_reserved(0, ALICE), 5);
+			assert_eq!(amount_owned(0, Marketplace::account_id()), 0);
+		})
+	}
+
+	#[test]
+	fn not_enough_owned() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::set_escrow_sale(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					1000,
+					6,
+					AssetKind::Native
+				),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::set_escrow_sale(RuntimeOrigin::none(), 0, 1000, 2, AssetKind::Native),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod buy_many {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	fn list_from_two_sellers() {
+		create_nft(5);
+		assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, CHARLIE));
+		assert_ok!(Marketplace::set_sale(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			1000,
+			3,
+			AssetKind::Native
+		));
+		assert_ok!(Marketplace::set_sale(
+			RuntimeOrigin::signed(CHARLIE),
+			0,
+			800,
+			2,
+			AssetKind::Native
+		));
+	}
+
+	#[test]
+	fn ok() {
+		let bob_funds = 5000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			list_from_two_sellers();
+
+			let purchases = vec![(ALICE, 2), (CHARLIE, 2)].try_into().unwrap();
+			assert_ok!(Marketplace::buy_many(
+				RuntimeOrigin::signed(BOB),
+				0,
+				3600,
+				purchases
+			));
+
+			assert_eq!(amount_owned(0, BOB), 4);
+			assert_eq!(Balances::free_balance(BOB), bob_funds - 3600);
+			assert_eq!(Marketplace::resource_for_sale(0, ALICE).unwrap().amount, 1);
+			assert!(Marketplace::resource_for_sale(0, CHARLIE).is_none());
+		})
+	}
+
+	#[test]
+	fn price_cap_exceeded() {
+		let bob_funds = 5000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			list_from_two_sellers();
+
+			let purchases = vec![(ALICE, 2), (CHARLIE, 2)].try_into().unwrap();
+			assert_noop!(
+				Marketplace::buy_many(RuntimeOrigin::signed(BOB), 0, 3599, purchases),
+				Error::<TestRuntime>::PriceCapExceeded
+			);
+		})
+	}
+
+	#[test]
+	fn reverted_when_one_purchase_fails() {
+		let bob_funds = 5000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			list_from_two_sellers();
+
+			let purchases = vec![(ALICE, 2), (CHARLIE, 3)].try_into().unwrap();
+			assert_noop!(
+				Marketplace::buy_many(RuntimeOrigin::signed(BOB), 0, 5000, purchases),
+				Error::<TestRuntime>::NotEnoughInSale
+			);
+		})
+	}
+
+	#[test]
+	fn asset_mismatch() {
+		let stablecoin = 7;
+		ExtBuilder::default().balances(vec![(BOB, 5000)]).build().execute_with(|| {
+			assert_ok!(Assets::force_create(
+				RuntimeOrigin::root(),
+				stablecoin.into(),
+				ALICE,
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				RuntimeOrigin::signed(ALICE),
+				stablecoin.into(),
+				BOB,
+				5000
+			));
+			list_from_two_sellers();
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(CHARLIE),
+				0,
+				800,
+				2,
+				AssetKind::Asset(stablecoin)
+			));
+
+			let purchases = vec![(ALICE, 1), (CHARLIE, 1)].try_into().unwrap();
+			assert_noop!(
+				Marketplace::buy_many(RuntimeOrigin::signed(BOB), 0, 5000, purchases),
+				Error::<TestRuntime>::AssetMismatch
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			let purchases = vec![(ALICE, 1)].try_into().unwrap();
+			assert_noop!(
+				Marketplace::buy_many(RuntimeOrigin::none(), 0, 1000, purchases),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod ask_index {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+	const DAVE: u64 = 4;
+
+	fn share_nft() {
+		create_nft(8);
+		assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, BOB));
+		assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, CHARLIE));
+		assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, DAVE));
+	}
+
+	fn list(seller: u64, price: u128) {
+		assert_ok!(Marketplace::set_sale(
+			RuntimeOrigin::signed(seller),
+			0,
+			price,
+			1,
+			AssetKind::Native
+		));
+	}
+
+	#[test]
+	fn sorted_by_price() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			list(ALICE, 1000);
+			list(BOB, 500);
+			list(CHARLIE, 1000);
+
+			assert_eq!(
+				Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+				vec![(500, BOB), (1000, ALICE), (1000, CHARLIE)]
+			);
+		})
+	}
+
+	#[test]
+	fn updated_with_the_listings() {
+		let dave_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(DAVE, dave_funds)])
+			.build()
+			.execute_with(|| {
+				share_nft();
+				list(ALICE, 1000);
+				list(BOB, 500);
+				list(CHARLIE, 800);
+
+				assert_ok!(Marketplace::update_price(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					400
+				));
+				assert_ok!(Marketplace::cancel_sale(RuntimeOrigin::signed(BOB), 0));
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(DAVE), 0, CHARLIE, 1));
+
+				assert_eq!(
+					Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+					vec![(400, ALICE)]
+				);
+			})
+	}
+
+	#[test]
+	fn dutch_sales_not_indexed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			list(ALICE, 1000);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+
+			assert!(Marketplace::ask_index(0, AssetKind::Native).is_empty());
+		})
+	}
+
+	#[test]
+	fn priciest_ask_evicted_when_full() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			list(ALICE, 1000);
+			list(BOB, 500);
+			list(CHARLIE, 800);
+			list(DAVE, 900);
+
+			assert_eq!(
+				Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+				vec![(500, BOB), (800, CHARLIE), (900, DAVE)]
+			);
+			assert!(Marketplace::resource_for_sale(0, ALICE).is_some());
+		})
+	}
+
+	#[test]
+	fn priciest_listing_not_indexed_when_full() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			list(ALICE, 1000);
+			list(BOB, 500);
+			list(CHARLIE, 800);
+			list(DAVE, 1200);
+
+			assert_eq!(
+				Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+				vec![(500, BOB), (800, CHARLIE), (1000, ALICE)]
+			);
+			assert_eq!(Marketplace::resource_for_sale(0, DAVE).unwrap().price, 1200);
+			assert_eq!(amount_reserved(0, DAVE), 1);
+		})
+	}
+
+	#[test]
+	fn left_out_ask_indexed_when_one_is_removed() {
+		let dave_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(DAVE, dave_funds)])
+			.build()
+			.execute_with(|| {
+				share_nft();
+				list(ALICE, 1000);
+				list(BOB, 500);
+				list(CHARLIE, 800);
+				list(DAVE, 1200);
+
+				assert_ok!(Marketplace::cancel_sale(RuntimeOrigin::signed(BOB), 0));
+				assert_eq!(
+					Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+					vec![(800, CHARLIE), (1000, ALICE), (1200, DAVE)]
+				);
+
+				// Evicted again by Bob, Dave comes back once Charlie is sold out
+				list(BOB, 500);
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(DAVE), 0, CHARLIE, 1));
+				assert_eq!(
+					Marketplace::ask_index(0, AssetKind::Native).into_inner(),
+					vec![(500, BOB), (1000, ALICE), (1200, DAVE)]
+				);
+			})
+	}
+
+	#[test]
+	fn best_ask() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			assert_eq!(Marketplace::best_ask(0, AssetKind::Native), None);
+
+			list(ALICE, 1000);
+			list(BOB, 500);
+
+			assert_eq!(
+				Marketplace::best_ask(0, AssetKind::Native),
+				Some(Ask {
+					seller: BOB,
+					price: 500,
+					amount: 1,
+					asset: AssetKind::Native
+				})
+			);
+			assert_eq!(Marketplace::best_ask(0, AssetKind::Asset(7)), None);
+		})
+	}
+
+	#[test]
+	fn asks() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			share_nft();
+			list(ALICE, 1000);
+			assert_ok!(Marketplace::set_dutch_sale(
+				RuntimeOrigin::signed(BOB),
+				0,
+				1000,
+				400,
+				100,
+				2,
+				AssetKind::Native
+			));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(CHARLIE),
+				0,
+				500,
+				1,
+				AssetKind::Asset(7)
+			));
+
+			System::set_block_number(4);
+
+			let asks = Marketplace::asks(0, AssetKind::Native);
+			assert_eq!(asks.len(), 2);
+			assert_eq!((asks[0].seller, asks[0].price), (BOB, 700));
+			assert_eq!((asks[1].seller, asks[1].price), (ALICE, 1000));
+			let asks = Marketplace::asks(0, AssetKind::Asset(7));
+			assert_eq!(asks.len(), 1);
+			assert_eq!((asks[0].seller, asks[0].price), (CHARLIE, 500));
+		})
+	}
+}
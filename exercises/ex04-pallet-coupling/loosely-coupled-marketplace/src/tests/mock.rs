@@ -1,10 +1,11 @@
 use crate as pallet_marketplace;
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, traits::AsEnsureOriginWithArg, PalletId};
+use frame_system::{EnsureRoot, EnsureSigned};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
@@ -16,6 +17,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system,
 		Balances: pallet_balances,
+		Assets: pallet_assets,
 
 		Marketplace: pallet_marketplace,
 		NFTs: pallet_marketplace_nfts,
@@ -54,7 +56,7 @@ impl frame_system::Config for TestRuntime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 0;
+	pub static ExistentialDeposit: u128 = 0;
 	pub const MaxLocks: u32 = 50;
 	pub const MaxReserves: u32 = 50;
 	pub const MaxFreezes: u32 = 100;
@@ -76,6 +78,37 @@ impl pallet_balances::Config for TestRuntime {
 	type MaxFreezes = MaxFreezes;
 }
 
+parameter_types! {
+	pub const AssetDeposit: u128 = 0;
+	pub const AssetAccountDeposit: u128 = 0;
+	pub const MetadataDepositBase: u128 = 0;
+	pub const MetadataDepositPerByte: u128 = 0;
+	pub const ApprovalDeposit: u128 = 0;
+	pub const StringLimit: u32 = 50;
+	pub const RemoveItemsLimit: u32 = 1000;
+}
+
+impl pallet_assets::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u128;
+	type RemoveItemsLimit = RemoveItemsLimit;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+	type ForceOrigin = EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const MaxLength: u32 = 20;
 }
@@ -86,16 +119,33 @@ impl pallet_marketplace_nfts::Config for TestRuntime {
 	type NFTId = u128;
 }
 
+parameter_types! {
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	pub const MaxPurchases: u32 = 3;
+	pub const MaxAsksPerResource: u32 = 3;
+	pub const MarketplaceFee: Permill = Permill::from_percent(10);
+	pub const FeeDestination: u64 = TREASURY;
+	pub const MarketplacePalletId: PalletId = PalletId(*b"py/mrket");
+}
+
 impl pallet_marketplace::Config for TestRuntime {
 	type Currency = Balances;
+	type Assets = Assets;
 	type RuntimeEvent = RuntimeEvent;
 	type Resource = NFTs;
 	type ResourceId = u128;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type MaxPurchases = MaxPurchases;
+	type MaxAsksPerResource = MaxAsksPerResource;
+	type MarketplaceFee = MarketplaceFee;
+	type FeeDestination = FeeDestination;
+	type PalletId = MarketplacePalletId;
 }
 
 // Mock users AccountId
 pub const ALICE: u64 = 1;
 pub const BOB: u64 = 2;
+pub const TREASURY: u64 = 42;
 
 #[derive(Default)]
 pub struct ExtBuilder {
@@ -110,6 +160,11 @@ impl ExtBuilder {
 		self
 	}
 
+	pub fn existential_deposit(self, existential_deposit: u128) -> Self {
+		ExistentialDeposit::set(existential_deposit);
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
 
@@ -1,20 +1,109 @@
-use crate::{BalanceOf, Config};
+use crate::{AssetIdOf, BalanceOf, Config};
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::pallet_prelude::RuntimeDebug;
+use frame_support::{
+	pallet_prelude::RuntimeDebug,
+	sp_runtime::{traits::Saturating, SaturatedConversion},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
 
+pub type AuctionId = u32;
+pub type OfferId = u32;
+
 #[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 #[codec(mel_bound())]
 pub struct SaleData<T: Config> {
+	/// The price of one unit, or the start price of a dutch sale
 	pub price: BalanceOf<T>,
 	pub amount: u128,
+	pub pricing: Pricing<T>,
+	/// The asset the price is paid in
+	pub asset: AssetKind<AssetIdOf<T>>,
+	pub custody: Custody,
 }
 
-impl<T: Config> Default for SaleData<T> {
-	fn default() -> Self {
-		Self {
-			price: Default::default(),
-			amount: Default::default(),
+impl<T: Config> SaleData<T> {
+	/// The price of one unit at block `now`
+	pub fn price_at(&self, now: BlockNumberFor<T>) -> BalanceOf<T> {
+		match &self.pricing {
+			Pricing::Fixed => self.price,
+			Pricing::Dutch {
+				floor_price,
+				decay_per_block,
+				start_block,
+			} => {
+				let elapsed: u128 = now.saturating_sub(*start_block).saturated_into();
+				let decay = decay_per_block.saturating_mul(elapsed.saturated_into());
+
+				self.price.saturating_sub(decay).max(*floor_price)
+			},
 		}
 	}
 }
+
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Custody {
+	/// The listed amount stays with the seller, reserved
+	Reserved,
+	/// The listed amount is held by the pallet account
+	Escrowed,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum AssetKind<AssetId> {
+	/// The native currency of the chain
+	Native,
+	/// An asset of `Config::Assets`
+	Asset(AssetId),
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub enum Pricing<T: Config> {
+	/// The price only changes when the seller updates it
+	Fixed,
+	/// The price decreases by `decay_per_block` every block since `start_block`, down to
+	/// `floor_price`
+	Dutch {
+		floor_price: BalanceOf<T>,
+		decay_per_block: BalanceOf<T>,
+		start_block: BlockNumberFor<T>,
+	},
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub struct AuctionData<T: Config> {
+	pub resource_id: T::ResourceId,
+	pub seller: T::AccountId,
+	pub amount: u128,
+	pub reserve_price: BalanceOf<T>,
+	pub end_block: BlockNumberFor<T>,
+	pub best_bid: Option<(T::AccountId, BalanceOf<T>)>,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub struct OfferData<T: Config> {
+	pub resource_id: T::ResourceId,
+	pub buyer: T::AccountId,
+	/// The only account allowed to accept the offer, any holder if `None`
+	pub seller: Option<T::AccountId>,
+	/// The price of one unit
+	pub price: BalanceOf<T>,
+	pub amount: u128,
+	pub expiry: BlockNumberFor<T>,
+}
+
+/// A listing as returned by the runtime API
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct Ask<AccountId, AssetKind, Balance> {
+	pub seller: AccountId,
+	/// The current price of one unit
+	pub price: Balance,
+	pub amount: u128,
+	pub asset: AssetKind,
+}
+
+pub type AskOf<T> =
+	Ask<<T as frame_system::Config>::AccountId, AssetKind<AssetIdOf<T>>, BalanceOf<T>>;
@@ -9,7 +9,9 @@ pub use pallet::*;
 mod tests;
 pub mod types;
 
-use frame_support::ensure;
+use frame_support::{
+	dispatch::DispatchResult, ensure, pallet_prelude::DispatchError, traits::tokens::Precision,
+};
 use sp_runtime::traits::{AtLeast32BitUnsigned, One, Saturating};
 use types::*;
 
@@ -50,6 +52,19 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn reserved)]
+	/// The part of the holdings of a specific account for a specific asset that cannot be moved.
+	pub(super) type Reserved<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::NFTId,
+		Blake2_128Concat,
+		T::AccountId,
+		u128,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn nonce)]
 	/// Nonce for id of the next created asset
@@ -76,16 +91,32 @@ pub mod pallet {
 			to: T::AccountId,
 			amount: u128,
 		},
+		/// Some assets have been reserved
+		Reserved {
+			asset_id: T::NFTId,
+			owner: T::AccountId,
+			amount: u128,
+		},
+		/// Some assets have been unreserved
+		Unreserved {
+			asset_id: T::NFTId,
+			owner: T::AccountId,
+			amount: u128,
+		},
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// The asset ID is unknown
 		Unknown,
-		/// The signing account does not own any amount of this asset
+		/// The signing account does not own any unreserved amount of this asset
 		NotOwned,
 		/// Supply must be positive
 		NoSupply,
+		/// The account does not own enough unreserved amount of this asset
+		NotEnoughFree,
+		/// The account does not have enough reserved amount of this asset
+		NotEnoughReserved,
 	}
 
 	#[pallet::call]
@@ -128,11 +159,9 @@ pub mod pallet {
 			UniqueAsset::<T>::try_mutate(asset_id, |maybe_details| -> DispatchResult {
 				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
 
-				let mut burned_amount = 0;
+				let burned_amount = amount.min(Self::free_balance(asset_id, origin.clone()));
 				Account::<T>::mutate(asset_id, origin.clone(), |balance| {
-					let old_balance = *balance;
-					*balance = balance.saturating_sub(amount);
-					burned_amount = old_balance - *balance;
+					*balance = balance.saturating_sub(burned_amount);
 				});
 
 				details.supply -= burned_amount;
@@ -172,13 +201,18 @@ pub mod pallet {
 
 impl<T: Config> Pallet<T> {
 	fn ensure_own_some(asset_id: T::NFTId, account: T::AccountId) -> Result<(), Error<T>> {
-		let owned = Self::account(asset_id, account);
+		let owned = Self::free_balance(asset_id, account);
 
 		ensure!(owned > 0, Error::<T>::NotOwned);
 
 		Ok(())
 	}
 
+	/// The amount of `nft_id` owned by `account` that is not reserved
+	pub fn free_balance(nft_id: T::NFTId, account: T::AccountId) -> u128 {
+		Self::account(nft_id, account.clone()).saturating_sub(Self::reserved(nft_id, account))
+	}
+
 	// Note that it must be public to be callable from pallet_tight_marketplace
 	pub fn unchecked_transfer(
 		nft_id: T::NFTId,
@@ -186,25 +220,86 @@ impl<T: Config> Pallet<T> {
 		to: T::AccountId,
 		amount: u128,
 	) -> u128 {
-		let mut transfered_amount = 0;
+		let transfered_amount = amount.min(Self::free_balance(nft_id, from.clone()));
+
+		Self::move_balance(nft_id, from, to, transfered_amount);
+
+		transfered_amount
+	}
+
+	pub fn do_reserve(nft_id: T::NFTId, account: T::AccountId, amount: u128) -> DispatchResult {
+		ensure!(
+			Self::free_balance(nft_id, account.clone()) >= amount,
+			Error::<T>::NotEnoughFree
+		);
+
+		Reserved::<T>::mutate(nft_id, account.clone(), |reserved| {
+			*reserved = reserved.saturating_add(amount);
+		});
+
+		Self::deposit_event(Event::<T>::Reserved {
+			asset_id: nft_id,
+			owner: account,
+			amount,
+		});
+
+		Ok(())
+	}
+
+	pub fn do_unreserve(nft_id: T::NFTId, account: T::AccountId, amount: u128) -> u128 {
+		let mut unreserved_amount = 0;
+		Reserved::<T>::mutate(nft_id, account.clone(), |reserved| {
+			unreserved_amount = amount.min(*reserved);
+			*reserved -= unreserved_amount;
+		});
+
+		if unreserved_amount > 0 {
+			Self::deposit_event(Event::<T>::Unreserved {
+				asset_id: nft_id,
+				owner: account,
+				amount: unreserved_amount,
+			});
+		}
+
+		unreserved_amount
+	}
+
+	pub fn do_transfer_reserved(
+		nft_id: T::NFTId,
+		from: T::AccountId,
+		to: T::AccountId,
+		amount: u128,
+		precision: Precision,
+	) -> Result<u128, DispatchError> {
+		let reserved = Self::reserved(nft_id, from.clone());
+		ensure!(
+			precision == Precision::BestEffort || reserved >= amount,
+			Error::<T>::NotEnoughReserved
+		);
+
+		let transfered_amount = amount.min(reserved);
+		Reserved::<T>::insert(nft_id, from.clone(), reserved - transfered_amount);
+
+		Self::move_balance(nft_id, from, to, transfered_amount);
+
+		Ok(transfered_amount)
+	}
+
+	fn move_balance(nft_id: T::NFTId, from: T::AccountId, to: T::AccountId, amount: u128) {
 		Account::<T>::mutate(nft_id, from.clone(), |balance| {
-			let old_balance = *balance;
 			*balance = balance.saturating_sub(amount);
-			transfered_amount = old_balance - *balance;
 		});
 
 		Account::<T>::mutate(nft_id, to.clone(), |balance| {
-			*balance = balance.saturating_add(transfered_amount);
+			*balance = balance.saturating_add(amount);
 		});
 
 		Self::deposit_event(Event::<T>::Transferred {
 			asset_id: nft_id,
 			from,
 			to,
-			amount: transfered_amount,
+			amount,
 		});
-
-		transfered_amount
 	}
 }
 
@@ -213,7 +308,41 @@ impl<T: Config> Sellable<T::AccountId, T::NFTId> for Pallet<T> {
 		Self::account(nft_id, account)
 	}
 
-	fn transfer(nft_id: T::NFTId, from: T::AccountId, to: T::AccountId, amount: u128) -> u128 {
-		Self::unchecked_transfer(nft_id, from, to, amount)
+	fn transfer(
+		nft_id: T::NFTId,
+		from: T::AccountId,
+		to: T::AccountId,
+		amount: u128,
+		precision: Precision,
+	) -> Result<u128, DispatchError> {
+		ensure!(
+			precision == Precision::BestEffort
+				|| Self::free_balance(nft_id, from.clone()) >= amount,
+			Error::<T>::NotEnoughFree
+		);
+
+		Ok(Self::unchecked_transfer(nft_id, from, to, amount))
+	}
+
+	fn amount_reserved(nft_id: T::NFTId, account: T::AccountId) -> u128 {
+		Self::reserved(nft_id, account)
+	}
+
+	fn reserve(nft_id: T::NFTId, account: T::AccountId, amount: u128) -> DispatchResult {
+		Self::do_reserve(nft_id, account, amount)
+	}
+
+	fn unreserve(nft_id: T::NFTId, account: T::AccountId, amount: u128) -> u128 {
+		Self::do_unreserve(nft_id, account, amount)
+	}
+
+	fn transfer_reserved(
+		nft_id: T::NFTId,
+		from: T::AccountId,
+		to: T::AccountId,
+		amount: u128,
+		precision: Precision,
+	) -> Result<u128, DispatchError> {
+		Self::do_transfer_reserved(nft_id, from, to, amount, precision)
 	}
 }
@@ -1,5 +1,5 @@
 use crate::{tests::mock::*, Error};
-use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin, traits::tokens::Precision};
 
 mod mint {
 	use super::*;
@@ -227,3 +227,186 @@ mod burn {
 		})
 	}
 }
+
+mod reserve {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+
+			assert_eq!(NFTs::account(0, ALICE), 5);
+			assert_eq!(NFTs::reserved(0, ALICE), 2);
+			assert_eq!(NFTs::free_balance(0, ALICE), 3);
+		})
+	}
+
+	#[test]
+	fn not_enough_free() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 4));
+
+			assert_noop!(
+				NFTs::do_reserve(0, ALICE, 2),
+				Error::<TestRuntime>::NotEnoughFree
+			);
+		})
+	}
+
+	#[test]
+	fn reserved_cannot_be_transferred() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 5, BOB));
+
+			assert_eq!(NFTs::account(0, ALICE), 2);
+			assert_eq!(NFTs::account(0, BOB), 3);
+
+			assert_noop!(
+				NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 1, BOB),
+				Error::<TestRuntime>::NotOwned
+			);
+		})
+	}
+
+	#[test]
+	fn reserved_cannot_be_burned() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+			assert_ok!(NFTs::burn(RuntimeOrigin::signed(ALICE), 0, 5));
+
+			assert_eq!(NFTs::unique_asset(0).unwrap().supply, 2);
+			assert_eq!(NFTs::account(0, ALICE), 2);
+		})
+	}
+}
+
+mod unreserve {
+	use super::*;
+
+	#[test]
+	fn ok_saturating() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+
+			assert_eq!(NFTs::do_unreserve(0, ALICE, 10), 2);
+			assert_eq!(NFTs::reserved(0, ALICE), 0);
+			assert_eq!(NFTs::free_balance(0, ALICE), 5);
+		})
+	}
+}
+
+mod transfer_reserved {
+	use super::*;
+
+	#[test]
+	fn ok_saturating() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+
+			assert_eq!(
+				NFTs::do_transfer_reserved(0, ALICE, BOB, 3, Precision::BestEffort),
+				Ok(2)
+			);
+			assert_eq!(NFTs::reserved(0, ALICE), 0);
+			assert_eq!(NFTs::account(0, ALICE), 3);
+			assert_eq!(NFTs::account(0, BOB), 2);
+			assert_eq!(NFTs::reserved(0, BOB), 0);
+		})
+	}
+
+	#[test]
+	fn exact() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+
+			assert_noop!(
+				NFTs::do_transfer_reserved(0, ALICE, BOB, 3, Precision::Exact),
+				Error::<TestRuntime>::NotEnoughReserved
+			);
+			assert_eq!(
+				NFTs::do_transfer_reserved(0, ALICE, BOB, 2, Precision::Exact),
+				Ok(2)
+			);
+		})
+	}
+}
+
+mod sellable_transfer {
+	use super::*;
+	use crate::types::Sellable;
+
+	#[test]
+	fn ok_saturating() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+
+			assert_eq!(
+				<NFTs as Sellable<u64, u128>>::transfer(0, ALICE, BOB, 10, Precision::BestEffort),
+				Ok(5)
+			);
+			assert_eq!(NFTs::account(0, BOB), 5);
+		})
+	}
+
+	#[test]
+	fn exact() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(NFTs::mint(
+				RuntimeOrigin::signed(ALICE),
+				"".as_bytes().to_vec().try_into().unwrap(),
+				5
+			));
+			assert_ok!(NFTs::do_reserve(0, ALICE, 2));
+
+			assert_noop!(
+				<NFTs as Sellable<u64, u128>>::transfer(0, ALICE, BOB, 4, Precision::Exact),
+				Error::<TestRuntime>::NotEnoughFree
+			);
+			assert_eq!(
+				<NFTs as Sellable<u64, u128>>::transfer(0, ALICE, BOB, 3, Precision::Exact),
+				Ok(3)
+			);
+		})
+	}
+}
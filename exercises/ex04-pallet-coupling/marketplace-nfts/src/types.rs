@@ -1,16 +1,41 @@
 use crate::Config;
 use codec::{Decode, Encode};
 use frame_support::{
-	pallet_prelude::{BoundedVec, MaxEncodedLen, RuntimeDebug},
-	traits::Get,
+	pallet_prelude::{BoundedVec, DispatchError, DispatchResult, MaxEncodedLen, RuntimeDebug},
+	traits::{tokens::Precision, Get},
 };
 use scale_info::TypeInfo;
 
 pub trait Sellable<AccountId, ResourceId> {
 	/// return the amount of `id` possessed by `account`
 	fn amount_owned(id: ResourceId, account: AccountId) -> u128;
-	/// transfer `amount` of the `id` resource, from `from` to `to`, and return the amount created
-	fn transfer(id: ResourceId, from: AccountId, to: AccountId, amount: u128) -> u128;
+	/// transfer `amount` of the `id` resource, from `from` to `to`, and return the amount
+	/// transferred. With `Precision::Exact` it fails unless the whole `amount` can be transferred,
+	/// with `Precision::BestEffort` it transfers as much as possible
+	fn transfer(
+		id: ResourceId,
+		from: AccountId,
+		to: AccountId,
+		amount: u128,
+		precision: Precision,
+	) -> Result<u128, DispatchError>;
+	/// return the amount of `id` reserved by `account`, this is part of the amount owned
+	fn amount_reserved(id: ResourceId, account: AccountId) -> u128;
+	/// reserve `amount` of the `id` resource owned by `account`, so that it cannot be transferred
+	/// anymore, fails if `account` does not own enough unreserved resource
+	fn reserve(id: ResourceId, account: AccountId, amount: u128) -> DispatchResult;
+	/// unreserve `amount` of the `id` resource reserved by `account`, and return the amount
+	/// unreserved
+	fn unreserve(id: ResourceId, account: AccountId, amount: u128) -> u128;
+	/// transfer `amount` of the `id` resource reserved by `from` to `to`, and return the amount
+	/// transferred. `precision` behaves as in `transfer`
+	fn transfer_reserved(
+		id: ResourceId,
+		from: AccountId,
+		to: AccountId,
+		amount: u128,
+		precision: Precision,
+	) -> Result<u128, DispatchError>;
 }
 
 pub type UniqueAssetId = u128;
@@ -8,8 +8,14 @@ pub mod types;
 
 use frame_support::{
 	ensure,
-	sp_runtime::traits::{CheckedConversion, CheckedMul},
-	traits::{Currency, ExistenceRequirement::KeepAlive},
+	sp_runtime::{
+		traits::{CheckedConversion, CheckedMul, Saturating, Zero},
+		Permill,
+	},
+	traits::{
+		tokens::Precision, BalanceStatus, Currency, ExistenceRequirement::KeepAlive,
+		ReservableCurrency,
+	},
 };
 use types::*;
 
@@ -28,7 +34,16 @@ pub mod pallet {
 		frame_system::Config + scale_info::TypeInfo + pallet_marketplace_nfts::Config
 	{
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		// The share of every sale paid to the creator of the NFT, unless the creator has set
+		// another one for this NFT
+		#[pallet::constant]
+		type DefaultRoyalty: Get<Permill>;
+
+		// The highest royalty a creator can set
+		#[pallet::constant]
+		type MaxRoyalty: Get<Permill>;
 	}
 
 	#[pallet::pallet]
@@ -41,6 +56,32 @@ pub mod pallet {
 		ListedForSale(T::NFTId, T::AccountId, BalanceOf<T>, u128),
 		// NFT has been sold (nft_id, seller, buyer, amount)
 		Sold(T::NFTId, T::AccountId, T::AccountId, u128),
+		/// Royalty of an NFT has been set by its creator, `None` meaning the default one
+		/// (nft_id, royalty)
+		RoyaltySet(T::NFTId, Option<Permill>),
+		/// Royalty has been paid to the creator of a sold NFT (nft_id, creator, amount)
+		RoyaltyPaid(T::NFTId, T::AccountId, BalanceOf<T>),
+		/// Expired or no longer backed sale has been removed (nft_id, seller)
+		SaleRemoved(T::NFTId, T::AccountId),
+		/// Bid on any NFT created by `creator` has been placed (bid_id, bidder, creator, price,
+		/// amount)
+		CollectionBidPlaced(
+			CollectionBidId,
+			T::AccountId,
+			T::AccountId,
+			BalanceOf<T>,
+			u128,
+		),
+		/// Collection bid has been filled, fully or partially (bid_id, nft_id, seller, amount)
+		CollectionBidAccepted(CollectionBidId, T::NFTId, T::AccountId, u128),
+		/// Collection bid has been cancelled and its remaining funds unreserved (bid_id)
+		CollectionBidCancelled(CollectionBidId),
+		/// Swap has been proposed (swap_id, proposer, counterparty)
+		SwapProposed(SwapId, T::AccountId, Option<T::AccountId>),
+		/// Swap has been accepted and executed (swap_id, accepter)
+		SwapAccepted(SwapId, T::AccountId),
+		/// Swap has been cancelled and the offer released (swap_id)
+		SwapCancelled(SwapId),
 	}
 
 	#[pallet::error]
@@ -50,6 +91,21 @@ pub mod pallet {
 		NotEnoughOwned,
 		SaleNotFound,
 		Overflow,
+		NFTNotFound,
+		NotCreator,
+		ExpiryInThePast,
+		SaleExpired,
+		BidNotFound,
+		NotBidder,
+		NotInCollection,
+		NotEnoughInBid,
+		BidNotFunded,
+		SwapNotFound,
+		NotSwapCounterparty,
+		NotProposer,
+		SwapWithSelf,
+		SwapNotFunded,
+		RoyaltyTooHigh,
 	}
 
 	#[pallet::storage]
@@ -64,6 +120,74 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn royalty)]
+	pub type Royalties<T: Config> = StorageMap<_, Blake2_128Concat, T::NFTId, Permill>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_collection_bid_id)]
+	pub type NextCollectionBidId<T: Config> = StorageValue<_, CollectionBidId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn collection_bid)]
+	pub type CollectionBids<T: Config> =
+		StorageMap<_, Blake2_128Concat, CollectionBidId, CollectionBid<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_swap_id)]
+	pub type NextSwapId<T: Config> = StorageValue<_, SwapId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn swap)]
+	pub type Swaps<T: Config> = StorageMap<_, Blake2_128Concat, SwapId, SwapProposal<T>>;
+
+	// The raw key of the last sale checked by `on_idle`, the next cleanup resumes after it
+	#[pallet::storage]
+	pub type CleanupCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			let per_sale = db_weight.reads_writes(2, 3);
+			let mut used_weight = db_weight.reads_writes(1, 1);
+
+			if remaining_weight.any_lt(used_weight.saturating_add(per_sale)) {
+				return Weight::zero()
+			}
+
+			let mut sales = match CleanupCursor::<T>::take() {
+				Some(cursor) => NFTsForSale::<T>::iter_from(cursor.into_inner()),
+				None => NFTsForSale::<T>::iter(),
+			};
+
+			while remaining_weight.all_gte(used_weight.saturating_add(per_sale)) {
+				// Every sale has been checked, the next cleanup starts over
+				let Some((nft_id, seller, sale_data)) = sales.next() else {
+					return used_weight
+				};
+				used_weight.saturating_accrue(per_sale);
+
+				let reserved =
+					pallet_marketplace_nfts::Pallet::<T>::reserved(nft_id, seller.clone());
+				if sale_data.is_expired(n) || reserved < sale_data.amount {
+					pallet_marketplace_nfts::Pallet::<T>::do_unreserve(
+						nft_id,
+						seller.clone(),
+						sale_data.amount,
+					);
+					NFTsForSale::<T>::remove(nft_id, seller.clone());
+
+					Self::deposit_event(Event::<T>::SaleRemoved(nft_id, seller));
+				}
+			}
+
+			CleanupCursor::<T>::put(BoundedVec::truncate_from(sales.last_raw_key().to_vec()));
+
+			used_weight
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
@@ -73,14 +197,34 @@ pub mod pallet {
 			nft_id: T::NFTId,
 			price: BalanceOf<T>,
 			amount: u128,
+			expires_at: Option<BlockNumberFor<T>>,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 
 			ensure!(amount > 0, Error::<T>::ZeroAmount);
-			let owned = pallet_marketplace_nfts::Pallet::<T>::account(nft_id, origin.clone());
-			ensure!(owned >= amount, Error::<T>::NotEnoughOwned);
+			if let Some(expires_at) = expires_at {
+				ensure!(
+					expires_at > frame_system::Pallet::<T>::block_number(),
+					Error::<T>::ExpiryInThePast
+				);
+			}
 
-			NFTsForSale::<T>::insert(nft_id, origin.clone(), SaleData { price, amount });
+			// Release what was listed before, so the new listing can reuse it
+			let listed = NFTsForSale::<T>::get(nft_id, origin.clone()).amount;
+			pallet_marketplace_nfts::Pallet::<T>::do_unreserve(nft_id, origin.clone(), listed);
+			pallet_marketplace_nfts::Pallet::<T>::do_reserve(nft_id, origin.clone(), amount)
+				.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+			NFTsForSale::<T>::insert(
+				nft_id,
+				origin.clone(),
+				SaleData {
+					price,
+					amount,
+					expires_at,
+					royalty: Self::royalty_of(nft_id),
+				},
+			);
 
 			Self::deposit_event(Event::<T>::ListedForSale(nft_id, origin, price, amount));
 
@@ -102,20 +246,38 @@ pub mod pallet {
 
 			ensure!(amount <= sale_data.amount, Error::<T>::NotEnoughInSale);
 			ensure!(sale_data.amount <= owned, Error::<T>::NotEnoughOwned);
+			ensure!(
+				!sale_data.is_expired(frame_system::Pallet::<T>::block_number()),
+				Error::<T>::SaleExpired
+			);
 
-			let total_to_pay = sale_data
-				.price
-				.checked_mul(&amount.checked_into().ok_or(Error::<T>::Overflow)?)
-				.ok_or(Error::<T>::Overflow)?;
+			let total_to_pay = Self::total_price(sale_data.price, amount)?;
 
-			<T as pallet::Config>::Currency::transfer(&buyer, &seller, total_to_pay, KeepAlive)?;
+			let creator = pallet_marketplace_nfts::Pallet::<T>::unique_asset(nft_id)
+				.ok_or(Error::<T>::NFTNotFound)?
+				.creator();
+			let royalty = Self::royalty_due(&creator, &seller, sale_data.royalty, total_to_pay);
 
-			pallet_marketplace_nfts::Pallet::<T>::unchecked_transfer(
+			<T as pallet::Config>::Currency::transfer(
+				&buyer,
+				&seller,
+				total_to_pay - royalty,
+				KeepAlive,
+			)?;
+			if !royalty.is_zero() {
+				<T as pallet::Config>::Currency::transfer(&buyer, &creator, royalty, KeepAlive)?;
+				Self::deposit_event(Event::<T>::RoyaltyPaid(nft_id, creator, royalty));
+			}
+
+			// Calls are transactional, so the payment is reverted if the NFT is not delivered
+			pallet_marketplace_nfts::Pallet::<T>::do_transfer_reserved(
 				nft_id,
 				seller.clone(),
 				buyer.clone(),
 				amount,
-			);
+				Precision::Exact,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
 
 			if amount == sale_data.amount {
 				NFTsForSale::<T>::remove(nft_id, seller.clone());
@@ -127,5 +289,308 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Set the share of the sales of an NFT paid to its creator, or fall back to the default
+		/// one with `None`. Sales listed before keep the royalty they were listed with.
+		#[pallet::call_index(2)]
+		#[pallet::weight(Weight::default())]
+		pub fn set_royalty(
+			origin: OriginFor<T>,
+			nft_id: T::NFTId,
+			royalty: Option<Permill>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let details = pallet_marketplace_nfts::Pallet::<T>::unique_asset(nft_id)
+				.ok_or(Error::<T>::NFTNotFound)?;
+			ensure!(details.creator() == origin, Error::<T>::NotCreator);
+			if let Some(royalty) = royalty {
+				ensure!(royalty <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+			}
+
+			Royalties::<T>::set(nft_id, royalty);
+
+			Self::deposit_event(Event::<T>::RoyaltySet(nft_id, royalty));
+
+			Ok(())
+		}
+
+		/// Bid `price` per unit for `amount` units of any NFT created by `creator`
+		#[pallet::call_index(3)]
+		#[pallet::weight(Weight::default())]
+		pub fn place_collection_bid(
+			origin: OriginFor<T>,
+			creator: T::AccountId,
+			price: BalanceOf<T>,
+			amount: u128,
+		) -> DispatchResult {
+			let bidder = ensure_signed(origin)?;
+
+			ensure!(amount > 0, Error::<T>::ZeroAmount);
+
+			<T as pallet::Config>::Currency::reserve(&bidder, Self::total_price(price, amount)?)?;
+
+			let bid_id = Self::next_collection_bid_id();
+			CollectionBids::<T>::insert(
+				bid_id,
+				CollectionBid {
+					bidder: bidder.clone(),
+					creator: creator.clone(),
+					price,
+					amount,
+				},
+			);
+			NextCollectionBidId::<T>::put(bid_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+
+			Self::deposit_event(Event::<T>::CollectionBidPlaced(
+				bid_id, bidder, creator, price, amount,
+			));
+
+			Ok(())
+		}
+
+		/// Sell `amount` units of `nft_id` to a collection bid it matches
+		#[pallet::call_index(4)]
+		#[pallet::weight(Weight::default())]
+		pub fn accept_collection_bid(
+			origin: OriginFor<T>,
+			bid_id: CollectionBidId,
+			nft_id: T::NFTId,
+			amount: u128,
+		) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
+			ensure!(amount > 0, Error::<T>::ZeroAmount);
+
+			let bid = Self::collection_bid(bid_id).ok_or(Error::<T>::BidNotFound)?;
+			ensure!(amount <= bid.amount, Error::<T>::NotEnoughInBid);
+
+			let creator = pallet_marketplace_nfts::Pallet::<T>::unique_asset(nft_id)
+				.ok_or(Error::<T>::NFTNotFound)?
+				.creator();
+			ensure!(creator == bid.creator, Error::<T>::NotInCollection);
+
+			// Listed amounts are reserved, so only the unlisted part can fill a bid
+			<pallet_marketplace_nfts::Pallet<T> as Sellable<T::AccountId, T::NFTId>>::transfer(
+				nft_id,
+				seller.clone(),
+				bid.bidder.clone(),
+				amount,
+				Precision::Exact,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+			let total_to_pay = Self::total_price(bid.price, amount)?;
+			let royalty =
+				Self::royalty_due(&creator, &seller, Self::royalty_of(nft_id), total_to_pay);
+
+			let mut not_paid = <T as pallet::Config>::Currency::repatriate_reserved(
+				&bid.bidder,
+				&seller,
+				total_to_pay - royalty,
+				BalanceStatus::Free,
+			)?;
+			if !royalty.is_zero() {
+				not_paid += <T as pallet::Config>::Currency::repatriate_reserved(
+					&bid.bidder,
+					&creator,
+					royalty,
+					BalanceStatus::Free,
+				)?;
+				Self::deposit_event(Event::<T>::RoyaltyPaid(nft_id, creator, royalty));
+			}
+			ensure!(not_paid.is_zero(), Error::<T>::BidNotFunded);
+
+			if amount == bid.amount {
+				CollectionBids::<T>::remove(bid_id);
+			} else {
+				CollectionBids::<T>::insert(
+					bid_id,
+					CollectionBid {
+						amount: bid.amount - amount,
+						..bid
+					},
+				);
+			}
+
+			Self::deposit_event(Event::<T>::CollectionBidAccepted(
+				bid_id, nft_id, seller, amount,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(Weight::default())]
+		pub fn cancel_collection_bid(
+			origin: OriginFor<T>,
+			bid_id: CollectionBidId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let bid = Self::collection_bid(bid_id).ok_or(Error::<T>::BidNotFound)?;
+			ensure!(who == bid.bidder, Error::<T>::NotBidder);
+
+			<T as pallet::Config>::Currency::unreserve(
+				&bid.bidder,
+				Self::total_price(bid.price, bid.amount)?,
+			);
+			CollectionBids::<T>::remove(bid_id);
+
+			Self::deposit_event(Event::<T>::CollectionBidCancelled(bid_id));
+
+			Ok(())
+		}
+
+		/// Offer `offered` NFTs, and optionally `top_up` in currency, in exchange for `wanted`
+		/// NFTs. Only `counterparty` can accept the swap if set, anyone owning `wanted` otherwise.
+		#[pallet::call_index(6)]
+		#[pallet::weight(Weight::default())]
+		pub fn propose_swap(
+			origin: OriginFor<T>,
+			offered: (T::NFTId, u128),
+			wanted: (T::NFTId, u128),
+			counterparty: Option<T::AccountId>,
+			top_up: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			let proposer = ensure_signed(origin)?;
+
+			ensure!(offered.1 > 0 && wanted.1 > 0, Error::<T>::ZeroAmount);
+			ensure!(
+				counterparty.as_ref() != Some(&proposer),
+				Error::<T>::SwapWithSelf
+			);
+
+			// The offer is locked until the swap is accepted or cancelled
+			pallet_marketplace_nfts::Pallet::<T>::do_reserve(
+				offered.0,
+				proposer.clone(),
+				offered.1,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
+			if let Some(top_up) = top_up {
+				<T as pallet::Config>::Currency::reserve(&proposer, top_up)?;
+			}
+
+			let swap_id = Self::next_swap_id();
+			Swaps::<T>::insert(
+				swap_id,
+				SwapProposal {
+					proposer: proposer.clone(),
+					offered,
+					wanted,
+					counterparty: counterparty.clone(),
+					top_up,
+				},
+			);
+			NextSwapId::<T>::put(swap_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+
+			Self::deposit_event(Event::<T>::SwapProposed(swap_id, proposer, counterparty));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(Weight::default())]
+		pub fn accept_swap(origin: OriginFor<T>, swap_id: SwapId) -> DispatchResult {
+			let accepter = ensure_signed(origin)?;
+
+			let swap = Swaps::<T>::take(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+
+			ensure!(accepter != swap.proposer, Error::<T>::SwapWithSelf);
+			ensure!(
+				swap.counterparty.as_ref().is_none_or(|counterparty| *counterparty == accepter),
+				Error::<T>::NotSwapCounterparty
+			);
+
+			// Calls are transactional, so both transfers happen or none does
+			<pallet_marketplace_nfts::Pallet<T> as Sellable<T::AccountId, T::NFTId>>::transfer(
+				swap.wanted.0,
+				accepter.clone(),
+				swap.proposer.clone(),
+				swap.wanted.1,
+				Precision::Exact,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
+			pallet_marketplace_nfts::Pallet::<T>::do_transfer_reserved(
+				swap.offered.0,
+				swap.proposer.clone(),
+				accepter.clone(),
+				swap.offered.1,
+				Precision::Exact,
+			)
+			.map_err(|_| Error::<T>::NotEnoughOwned)?;
+
+			if let Some(top_up) = swap.top_up {
+				let not_paid = <T as pallet::Config>::Currency::repatriate_reserved(
+					&swap.proposer,
+					&accepter,
+					top_up,
+					BalanceStatus::Free,
+				)?;
+				ensure!(not_paid.is_zero(), Error::<T>::SwapNotFunded);
+			}
+
+			Self::deposit_event(Event::<T>::SwapAccepted(swap_id, accepter));
+
+			Ok(())
+		}
+
+		#[pallet::call_index(8)]
+		#[pallet::weight(Weight::default())]
+		pub fn cancel_swap(origin: OriginFor<T>, swap_id: SwapId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let swap = Swaps::<T>::take(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+			ensure!(who == swap.proposer, Error::<T>::NotProposer);
+
+			pallet_marketplace_nfts::Pallet::<T>::do_unreserve(
+				swap.offered.0,
+				swap.proposer.clone(),
+				swap.offered.1,
+			);
+			if let Some(top_up) = swap.top_up {
+				<T as pallet::Config>::Currency::unreserve(&swap.proposer, top_up);
+			}
+
+			Self::deposit_event(Event::<T>::SwapCancelled(swap_id));
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The share of the sales of `nft_id` paid to its creator
+	pub fn royalty_of(nft_id: T::NFTId) -> Permill {
+		Self::royalty(nft_id).unwrap_or_else(T::DefaultRoyalty::get)
+	}
+
+	/// The share of `total` paid to `creator` on a sale by `seller`. It is waived when the creator
+	/// is the seller, who already gets the whole price, or when it is too small to open the
+	/// account of the creator, as paying it would fail the sale.
+	fn royalty_due(
+		creator: &T::AccountId,
+		seller: &T::AccountId,
+		royalty: Permill,
+		total: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		let royalty = royalty * total;
+		let creator_balance = <T as pallet::Config>::Currency::total_balance(creator);
+		if creator == seller
+			|| creator_balance.saturating_add(royalty)
+				< <T as pallet::Config>::Currency::minimum_balance()
+		{
+			Zero::zero()
+		} else {
+			royalty
+		}
+	}
+
+	/// The price of `amount` units at `price` each
+	fn total_price(price: BalanceOf<T>, amount: u128) -> Result<BalanceOf<T>, Error<T>> {
+		price
+			.checked_mul(&amount.checked_into().ok_or(Error::<T>::Overflow)?)
+			.ok_or(Error::<T>::Overflow)
 	}
 }
@@ -1,5 +1,11 @@
 use crate::{tests::mock::*, Error};
-use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+use frame_support::{
+	assert_noop, assert_ok,
+	error::BadOrigin,
+	sp_runtime::Permill,
+	traits::{Get, OnIdle},
+	weights::Weight,
+};
 
 fn create_nft(amount: u128) {
 	let metadata = "Some metadata".as_bytes().to_vec().try_into().unwrap();
@@ -10,6 +16,10 @@ fn amount_owned(resource_id: u128, address: u64) -> u128 {
 	NFTs::account(resource_id, address)
 }
 
+fn amount_reserved(resource_id: u128, address: u64) -> u128 {
+	NFTs::reserved(resource_id, address)
+}
+
 mod set_sale {
 	use super::*;
 
@@ -24,12 +34,40 @@ mod set_sale {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				price,
-				amount
+				amount,
+				None
 			));
 
 			let sale = Marketplace::nft_for_sale(0, ALICE);
 			assert_eq!(sale.price, price);
 			assert_eq!(sale.amount, amount);
+			assert_eq!(amount_reserved(0, ALICE), amount);
+		})
+	}
+
+	#[test]
+	fn relist() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				4,
+				None
+			));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				500,
+				5,
+				None
+			));
+
+			let sale = Marketplace::nft_for_sale(0, ALICE);
+			assert_eq!(sale.price, 500);
+			assert_eq!(sale.amount, 5);
+			assert_eq!(amount_reserved(0, ALICE), 5);
 		})
 	}
 
@@ -37,7 +75,7 @@ mod set_sale {
 	fn nft_does_not_exist() {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 2),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 2, None),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
 		})
@@ -48,7 +86,7 @@ mod set_sale {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			create_nft(5);
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 0),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 0, None),
 				Error::<TestRuntime>::ZeroAmount
 			);
 		})
@@ -59,7 +97,7 @@ mod set_sale {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			create_nft(5);
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 10),
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 10, None),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
 		})
@@ -69,7 +107,7 @@ mod set_sale {
 	fn must_be_signed() {
 		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
 			assert_noop!(
-				Marketplace::set_sale(RuntimeOrigin::none(), 0, 1000, 2),
+				Marketplace::set_sale(RuntimeOrigin::none(), 0, 1000, 2, None),
 				BadOrigin
 			);
 		})
@@ -92,7 +130,8 @@ mod buy {
 					RuntimeOrigin::signed(ALICE),
 					0,
 					price,
-					2
+					2,
+					None
 				));
 
 				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
@@ -117,7 +156,8 @@ mod buy {
 					RuntimeOrigin::signed(ALICE),
 					0,
 					price,
-					2
+					2,
+					None
 				));
 
 				let amount_buy = 2;
@@ -144,7 +184,8 @@ mod buy {
 				RuntimeOrigin::signed(ALICE),
 				0,
 				1000,
-				2
+				2,
+				None
 			));
 
 			assert_noop!(
@@ -155,22 +196,51 @@ mod buy {
 	}
 
 	#[test]
-	fn not_enough_owned() {
-		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+	fn listed_amount_is_reserved() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
 			create_nft(5);
 			assert_ok!(Marketplace::set_sale(
 				RuntimeOrigin::signed(ALICE),
 				0,
 				1000,
-				2
+				2,
+				None
 			));
 
+			// Only the unlisted part can be moved away
 			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 4, 0));
+			assert_eq!(amount_owned(0, ALICE), 2);
+
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 2));
+
+			assert_eq!(amount_owned(0, ALICE), 0);
+			assert_eq!(amount_owned(0, BOB), 2);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn payment_reverted_when_not_delivered() {
+		let bob_funds = 2000;
+		ExtBuilder::default().balances(vec![(BOB, bob_funds)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				None
+			));
+
+			// The listed amount is not reserved anymore
+			NFTs::do_unreserve(0, ALICE, 2);
 
 			assert_noop!(
 				Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 2),
 				Error::<TestRuntime>::NotEnoughOwned
 			);
+			assert_eq!(bob_funds, Balances::free_balance(BOB));
 		})
 	}
 
@@ -184,3 +254,775 @@ mod buy {
 		})
 	}
 }
+
+mod royalties {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	fn list_by_bob() {
+		create_nft(5);
+		assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, BOB));
+		assert_ok!(Marketplace::set_sale(
+			RuntimeOrigin::signed(BOB),
+			0,
+			1000,
+			2,
+			None
+		));
+	}
+
+	#[test]
+	fn default_royalty_paid() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				list_by_bob();
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(CHARLIE), 0, BOB, 1));
+
+				assert_eq!(Balances::free_balance(CHARLIE), charlie_funds - 1000);
+				assert_eq!(Balances::free_balance(BOB), 950);
+				assert_eq!(Balances::free_balance(ALICE), 50);
+				System::assert_has_event(crate::Event::RoyaltyPaid(0, ALICE, 50).into());
+			})
+	}
+
+	#[test]
+	fn royalty_below_existential_deposit_waived() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.existential_deposit(100)
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				list_by_bob();
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(CHARLIE), 0, BOB, 1));
+
+				assert_eq!(Balances::free_balance(CHARLIE), charlie_funds - 1000);
+				assert_eq!(Balances::free_balance(BOB), 1000);
+				assert_eq!(Balances::free_balance(ALICE), 0);
+				assert!(!System::events().iter().any(|record| matches!(
+					record.event,
+					RuntimeEvent::Marketplace(crate::Event::RoyaltyPaid(..))
+				)));
+			})
+	}
+
+	#[test]
+	fn asset_royalty_paid() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				assert_ok!(Marketplace::set_royalty(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					Some(Permill::from_percent(20))
+				));
+				assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, BOB));
+				assert_ok!(Marketplace::set_sale(
+					RuntimeOrigin::signed(BOB),
+					0,
+					1000,
+					2,
+					None
+				));
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(CHARLIE), 0, BOB, 2));
+
+				assert_eq!(Balances::free_balance(BOB), 1600);
+				assert_eq!(Balances::free_balance(ALICE), 400);
+			})
+	}
+
+	#[test]
+	fn royalty_fixed_at_listing() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				list_by_bob();
+				assert_ok!(Marketplace::set_royalty(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					Some(Permill::from_percent(50))
+				));
+				assert_ok!(Marketplace::buy(RuntimeOrigin::signed(CHARLIE), 0, BOB, 2));
+
+				assert_eq!(Balances::free_balance(BOB), 1900);
+				assert_eq!(Balances::free_balance(ALICE), 100);
+			})
+	}
+
+	#[test]
+	fn no_royalty_when_creator_sells() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				assert_ok!(Marketplace::set_sale(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					1000,
+					2,
+					None
+				));
+				assert_ok!(Marketplace::buy(
+					RuntimeOrigin::signed(CHARLIE),
+					0,
+					ALICE,
+					1
+				));
+
+				assert_eq!(Balances::free_balance(ALICE), 1000);
+				System::assert_last_event(crate::Event::Sold(0, ALICE, CHARLIE, 1).into());
+			})
+	}
+
+	#[test]
+	fn set_royalty() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			let royalty = Permill::from_percent(20);
+
+			assert_ok!(Marketplace::set_royalty(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				Some(royalty)
+			));
+			assert_eq!(Marketplace::royalty_of(0), royalty);
+			System::assert_last_event(crate::Event::RoyaltySet(0, Some(royalty)).into());
+
+			assert_ok!(Marketplace::set_royalty(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				None
+			));
+			assert_eq!(Marketplace::royalty_of(0), DefaultRoyalty::get());
+		})
+	}
+
+	#[test]
+	fn royalty_too_high() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::set_royalty(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					Some(Permill::from_percent(51))
+				),
+				Error::<TestRuntime>::RoyaltyTooHigh
+			);
+		})
+	}
+
+	#[test]
+	fn not_creator() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::set_royalty(RuntimeOrigin::signed(BOB), 0, None),
+				Error::<TestRuntime>::NotCreator
+			);
+		})
+	}
+
+	#[test]
+	fn nft_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::set_royalty(RuntimeOrigin::signed(ALICE), 0, None),
+				Error::<TestRuntime>::NFTNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::set_royalty(RuntimeOrigin::none(), 0, None),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod expiry {
+	use super::*;
+
+	#[test]
+	fn set_sale() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				Some(10)
+			));
+
+			assert_eq!(Marketplace::nft_for_sale(0, ALICE).expires_at, Some(10));
+		})
+	}
+
+	#[test]
+	fn expiry_in_the_past() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_noop!(
+				Marketplace::set_sale(RuntimeOrigin::signed(ALICE), 0, 1000, 2, Some(1)),
+				Error::<TestRuntime>::ExpiryInThePast
+			);
+		})
+	}
+
+	#[test]
+	fn buy_expired() {
+		ExtBuilder::default().balances(vec![(BOB, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				Some(10)
+			));
+
+			System::set_block_number(9);
+			assert_ok!(Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1));
+
+			System::set_block_number(10);
+			assert_noop!(
+				Marketplace::buy(RuntimeOrigin::signed(BOB), 0, ALICE, 1),
+				Error::<TestRuntime>::SaleExpired
+			);
+		})
+	}
+
+	#[test]
+	fn expired_sales_removed_on_idle() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, BOB));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				Some(10)
+			));
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(BOB),
+				0,
+				1000,
+				2,
+				None
+			));
+
+			System::set_block_number(10);
+			<Marketplace as OnIdle<u64>>::on_idle(10, Weight::MAX);
+
+			assert_eq!(Marketplace::nft_for_sale(0, ALICE).amount, 0);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(Marketplace::nft_for_sale(0, BOB).amount, 2);
+			assert_eq!(amount_reserved(0, BOB), 2);
+			System::assert_has_event(crate::Event::SaleRemoved(0, ALICE).into());
+		})
+	}
+
+	#[test]
+	fn unbacked_sales_removed_on_idle() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				2,
+				None
+			));
+
+			// The listed amount is not reserved anymore
+			NFTs::do_unreserve(0, ALICE, 2);
+
+			<Marketplace as OnIdle<u64>>::on_idle(1, Weight::MAX);
+
+			assert_eq!(Marketplace::nft_for_sale(0, ALICE).amount, 0);
+			System::assert_last_event(crate::Event::SaleRemoved(0, ALICE).into());
+		})
+	}
+
+	#[test]
+	fn cleanup_resumed_from_cursor() {
+		const CHARLIE: u64 = 3;
+
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nft(6);
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, BOB));
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 2, CHARLIE));
+			for seller in [ALICE, BOB, CHARLIE] {
+				assert_ok!(Marketplace::set_sale(
+					RuntimeOrigin::signed(seller),
+					0,
+					1000,
+					2,
+					Some(10)
+				));
+			}
+
+			// The cursor plus two sales, with reads of 1 and writes of 10
+			let db_weight = <TestRuntime as frame_system::Config>::DbWeight::get();
+			let two_sales = db_weight.reads_writes(1 + 2 * 2, 1 + 2 * 3);
+
+			System::set_block_number(10);
+			assert_eq!(
+				<Marketplace as OnIdle<u64>>::on_idle(10, two_sales),
+				two_sales
+			);
+			assert_eq!(crate::NFTsForSale::<TestRuntime>::iter().count(), 1);
+			assert!(crate::CleanupCursor::<TestRuntime>::get().is_some());
+
+			assert_eq!(
+				<Marketplace as OnIdle<u64>>::on_idle(10, two_sales),
+				db_weight.reads_writes(1 + 2, 1 + 3)
+			);
+			assert_eq!(crate::NFTsForSale::<TestRuntime>::iter().count(), 0);
+			assert!(crate::CleanupCursor::<TestRuntime>::get().is_none());
+			for seller in [ALICE, BOB, CHARLIE] {
+				assert_eq!(amount_reserved(0, seller), 0);
+			}
+		})
+	}
+}
+
+mod collection_bids {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	fn place_bid() {
+		assert_ok!(Marketplace::place_collection_bid(
+			RuntimeOrigin::signed(CHARLIE),
+			ALICE,
+			300,
+			3
+		));
+	}
+
+	#[test]
+	fn place() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			place_bid();
+
+			let bid = Marketplace::collection_bid(0).unwrap();
+			assert_eq!(bid.bidder, CHARLIE);
+			assert_eq!(bid.creator, ALICE);
+			assert_eq!(bid.price, 300);
+			assert_eq!(bid.amount, 3);
+			assert_eq!(Marketplace::next_collection_bid_id(), 1);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 900);
+			System::assert_last_event(
+				crate::Event::CollectionBidPlaced(0, CHARLIE, ALICE, 300, 3).into(),
+			);
+		})
+	}
+
+	#[test]
+	fn accept_by_creator() {
+		let charlie_funds = 2000;
+		ExtBuilder::default()
+			.balances(vec![(CHARLIE, charlie_funds)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				place_bid();
+
+				assert_ok!(Marketplace::accept_collection_bid(
+					RuntimeOrigin::signed(ALICE),
+					0,
+					0,
+					2
+				));
+
+				assert_eq!(amount_owned(0, CHARLIE), 2);
+				assert_eq!(amount_owned(0, ALICE), 3);
+				assert_eq!(Balances::free_balance(ALICE), 600);
+				assert_eq!(Balances::reserved_balance(CHARLIE), 300);
+				assert_eq!(Balances::free_balance(CHARLIE), charlie_funds - 900);
+				assert_eq!(Marketplace::collection_bid(0).unwrap().amount, 1);
+				System::assert_last_event(
+					crate::Event::CollectionBidAccepted(0, 0, ALICE, 2).into(),
+				);
+			})
+	}
+
+	#[test]
+	fn accept_pays_royalty() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 3, BOB));
+			place_bid();
+
+			assert_ok!(Marketplace::accept_collection_bid(
+				RuntimeOrigin::signed(BOB),
+				0,
+				0,
+				3
+			));
+
+			assert!(Marketplace::collection_bid(0).is_none());
+			assert_eq!(amount_owned(0, CHARLIE), 3);
+			assert_eq!(Balances::free_balance(BOB), 855);
+			assert_eq!(Balances::free_balance(ALICE), 45);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+		})
+	}
+
+	#[test]
+	fn accept_waives_royalty_below_existential_deposit() {
+		ExtBuilder::default()
+			.existential_deposit(100)
+			.balances(vec![(CHARLIE, 2000)])
+			.build()
+			.execute_with(|| {
+				create_nft(5);
+				assert_ok!(NFTs::transfer(RuntimeOrigin::signed(ALICE), 0, 3, BOB));
+				place_bid();
+
+				assert_ok!(Marketplace::accept_collection_bid(
+					RuntimeOrigin::signed(BOB),
+					0,
+					0,
+					1
+				));
+
+				assert_eq!(Balances::free_balance(BOB), 300);
+				assert_eq!(Balances::free_balance(ALICE), 0);
+				assert_eq!(Balances::reserved_balance(CHARLIE), 600);
+			})
+	}
+
+	#[test]
+	fn not_in_collection() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			let metadata = "Other metadata".as_bytes().to_vec().try_into().unwrap();
+			assert_ok!(NFTs::mint(RuntimeOrigin::signed(BOB), metadata, 5));
+			place_bid();
+
+			assert_noop!(
+				Marketplace::accept_collection_bid(RuntimeOrigin::signed(BOB), 0, 0, 1),
+				Error::<TestRuntime>::NotInCollection
+			);
+		})
+	}
+
+	#[test]
+	fn listed_amount_cannot_fill_bid() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			assert_ok!(Marketplace::set_sale(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				1000,
+				4,
+				None
+			));
+			place_bid();
+
+			assert_noop!(
+				Marketplace::accept_collection_bid(RuntimeOrigin::signed(ALICE), 0, 0, 2),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn not_enough_in_bid() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			create_nft(5);
+			place_bid();
+
+			assert_noop!(
+				Marketplace::accept_collection_bid(RuntimeOrigin::signed(ALICE), 0, 0, 4),
+				Error::<TestRuntime>::NotEnoughInBid
+			);
+		})
+	}
+
+	#[test]
+	fn cancel() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			place_bid();
+
+			assert_ok!(Marketplace::cancel_collection_bid(
+				RuntimeOrigin::signed(CHARLIE),
+				0
+			));
+
+			assert!(Marketplace::collection_bid(0).is_none());
+			assert_eq!(Balances::free_balance(CHARLIE), 2000);
+			System::assert_last_event(crate::Event::CollectionBidCancelled(0).into());
+		})
+	}
+
+	#[test]
+	fn cancel_not_bidder() {
+		ExtBuilder::default().balances(vec![(CHARLIE, 2000)]).build().execute_with(|| {
+			place_bid();
+
+			assert_noop!(
+				Marketplace::cancel_collection_bid(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::NotBidder
+			);
+		})
+	}
+
+	#[test]
+	fn bid_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::accept_collection_bid(RuntimeOrigin::signed(ALICE), 0, 0, 1),
+				Error::<TestRuntime>::BidNotFound
+			);
+			assert_noop!(
+				Marketplace::cancel_collection_bid(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::BidNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::place_collection_bid(RuntimeOrigin::none(), ALICE, 300, 3),
+				BadOrigin
+			);
+			assert_noop!(
+				Marketplace::accept_collection_bid(RuntimeOrigin::none(), 0, 0, 1),
+				BadOrigin
+			);
+			assert_noop!(
+				Marketplace::cancel_collection_bid(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod swaps {
+	use super::*;
+
+	const CHARLIE: u64 = 3;
+
+	// ALICE owns 5 of NFT 0 and BOB owns 5 of NFT 1
+	fn create_nfts() {
+		create_nft(5);
+		let metadata = "Other metadata".as_bytes().to_vec().try_into().unwrap();
+		assert_ok!(NFTs::mint(RuntimeOrigin::signed(BOB), metadata, 5));
+	}
+
+	#[test]
+	fn propose() {
+		ExtBuilder::default().balances(vec![(ALICE, 1000)]).build().execute_with(|| {
+			create_nfts();
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 3),
+				Some(BOB),
+				Some(100)
+			));
+
+			let swap = Marketplace::swap(0).unwrap();
+			assert_eq!(swap.proposer, ALICE);
+			assert_eq!(swap.offered, (0, 2));
+			assert_eq!(swap.wanted, (1, 3));
+			assert_eq!(Marketplace::next_swap_id(), 1);
+			assert_eq!(amount_reserved(0, ALICE), 2);
+			assert_eq!(Balances::reserved_balance(ALICE), 100);
+			System::assert_last_event(crate::Event::SwapProposed(0, ALICE, Some(BOB)).into());
+		})
+	}
+
+	#[test]
+	fn accept() {
+		ExtBuilder::default().balances(vec![(ALICE, 1000)]).build().execute_with(|| {
+			create_nfts();
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 3),
+				None,
+				Some(100)
+			));
+
+			assert_ok!(Marketplace::accept_swap(RuntimeOrigin::signed(BOB), 0));
+
+			assert!(Marketplace::swap(0).is_none());
+			assert_eq!(amount_owned(0, ALICE), 3);
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(amount_owned(1, ALICE), 3);
+			assert_eq!(amount_owned(0, BOB), 2);
+			assert_eq!(amount_owned(1, BOB), 2);
+			assert_eq!(Balances::free_balance(ALICE), 900);
+			assert_eq!(Balances::free_balance(BOB), 100);
+			System::assert_last_event(crate::Event::SwapAccepted(0, BOB).into());
+		})
+	}
+
+	#[test]
+	fn not_enough_wanted() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nfts();
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 6),
+				None,
+				None
+			));
+
+			assert_noop!(
+				Marketplace::accept_swap(RuntimeOrigin::signed(BOB), 0),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn not_counterparty() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nfts();
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 3),
+				Some(CHARLIE),
+				None
+			));
+
+			assert_noop!(
+				Marketplace::accept_swap(RuntimeOrigin::signed(BOB), 0),
+				Error::<TestRuntime>::NotSwapCounterparty
+			);
+		})
+	}
+
+	#[test]
+	fn swap_with_self() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nfts();
+			assert_noop!(
+				Marketplace::propose_swap(
+					RuntimeOrigin::signed(ALICE),
+					(0, 2),
+					(1, 3),
+					Some(ALICE),
+					None
+				),
+				Error::<TestRuntime>::SwapWithSelf
+			);
+
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 3),
+				None,
+				None
+			));
+			assert_noop!(
+				Marketplace::accept_swap(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::SwapWithSelf
+			);
+		})
+	}
+
+	#[test]
+	fn not_enough_offered() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nfts();
+			assert_noop!(
+				Marketplace::propose_swap(RuntimeOrigin::signed(ALICE), (0, 6), (1, 3), None, None),
+				Error::<TestRuntime>::NotEnoughOwned
+			);
+		})
+	}
+
+	#[test]
+	fn zero_amount() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			create_nfts();
+			assert_noop!(
+				Marketplace::propose_swap(RuntimeOrigin::signed(ALICE), (0, 2), (1, 0), None, None),
+				Error::<TestRuntime>::ZeroAmount
+			);
+		})
+	}
+
+	#[test]
+	fn cancel() {
+		ExtBuilder::default().balances(vec![(ALICE, 1000)]).build().execute_with(|| {
+			create_nfts();
+			assert_ok!(Marketplace::propose_swap(
+				RuntimeOrigin::signed(ALICE),
+				(0, 2),
+				(1, 3),
+				None,
+				Some(100)
+			));
+
+			assert_noop!(
+				Marketplace::cancel_swap(RuntimeOrigin::signed(BOB), 0),
+				Error::<TestRuntime>::NotProposer
+			);
+			assert_ok!(Marketplace::cancel_swap(RuntimeOrigin::signed(ALICE), 0));
+
+			assert!(Marketplace::swap(0).is_none());
+			assert_eq!(amount_reserved(0, ALICE), 0);
+			assert_eq!(Balances::free_balance(ALICE), 1000);
+			System::assert_last_event(crate::Event::SwapCancelled(0).into());
+		})
+	}
+
+	#[test]
+	fn swap_not_found() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::accept_swap(RuntimeOrigin::signed(BOB), 0),
+				Error::<TestRuntime>::SwapNotFound
+			);
+			assert_noop!(
+				Marketplace::cancel_swap(RuntimeOrigin::signed(ALICE), 0),
+				Error::<TestRuntime>::SwapNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn must_be_signed() {
+		ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+			assert_noop!(
+				Marketplace::propose_swap(RuntimeOrigin::none(), (0, 2), (1, 3), None, None),
+				BadOrigin
+			);
+			assert_noop!(
+				Marketplace::accept_swap(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+			assert_noop!(
+				Marketplace::cancel_swap(RuntimeOrigin::none(), 0),
+				BadOrigin
+			);
+		})
+	}
+}
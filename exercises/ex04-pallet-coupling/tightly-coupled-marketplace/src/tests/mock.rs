@@ -1,10 +1,10 @@
 use crate as pallet_marketplace;
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, weights::RuntimeDbWeight};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
@@ -25,6 +25,7 @@ frame_support::construct_runtime!(
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 10 };
 }
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
@@ -36,7 +37,7 @@ impl frame_system::Config for TestRuntime {
 	type BlockLength = ();
 	type BlockWeights = ();
 	type RuntimeCall = RuntimeCall;
-	type DbWeight = ();
+	type DbWeight = DbWeight;
 	type RuntimeEvent = RuntimeEvent;
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
@@ -54,7 +55,7 @@ impl frame_system::Config for TestRuntime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 0;
+	pub static ExistentialDeposit: u128 = 0;
 	pub const MaxLocks: u32 = 50;
 	pub const MaxReserves: u32 = 50;
 	pub const MaxFreezes: u32 = 100;
@@ -86,9 +87,16 @@ impl pallet_marketplace_nfts::Config for TestRuntime {
 	type NFTId = u128;
 }
 
+parameter_types! {
+	pub const DefaultRoyalty: Permill = Permill::from_percent(5);
+	pub const MaxRoyalty: Permill = Permill::from_percent(50);
+}
+
 impl pallet_marketplace::Config for TestRuntime {
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
+	type DefaultRoyalty = DefaultRoyalty;
+	type MaxRoyalty = MaxRoyalty;
 }
 
 // Mock users AccountId
@@ -108,6 +116,11 @@ impl ExtBuilder {
 		self
 	}
 
+	pub fn existential_deposit(self, existential_deposit: u128) -> Self {
+		ExistentialDeposit::set(existential_deposit);
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
 
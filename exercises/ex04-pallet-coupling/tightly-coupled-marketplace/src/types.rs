@@ -1,13 +1,28 @@
 use crate::{BalanceOf, Config};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::pallet_prelude::RuntimeDebug;
+use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
+use sp_runtime::Permill;
+
+pub type CollectionBidId = u32;
+pub type SwapId = u32;
 
 #[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 #[codec(mel_bound())]
 pub struct SaleData<T: Config> {
 	pub price: BalanceOf<T>,
 	pub amount: u128,
+	/// The block from which the sale cannot be bought anymore, if any
+	pub expires_at: Option<BlockNumberFor<T>>,
+	/// The royalty of the NFT when it was listed, so later changes do not affect the sale
+	pub royalty: Permill,
+}
+
+impl<T: Config> SaleData<T> {
+	pub fn is_expired(&self, now: BlockNumberFor<T>) -> bool {
+		self.expires_at.is_some_and(|expires_at| now >= expires_at)
+	}
 }
 
 impl<T: Config> Default for SaleData<T> {
@@ -15,6 +30,33 @@ impl<T: Config> Default for SaleData<T> {
 		Self {
 			price: Default::default(),
 			amount: Default::default(),
+			expires_at: None,
+			royalty: Permill::zero(),
 		}
 	}
 }
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub struct CollectionBid<T: Config> {
+	pub bidder: T::AccountId,
+	/// Any NFT created by this account can fill the bid
+	pub creator: T::AccountId,
+	/// The price of one unit
+	pub price: BalanceOf<T>,
+	pub amount: u128,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[codec(mel_bound())]
+pub struct SwapProposal<T: Config> {
+	pub proposer: T::AccountId,
+	/// The NFT and amount given by the proposer, reserved until the swap ends
+	pub offered: (T::NFTId, u128),
+	/// The NFT and amount asked in exchange
+	pub wanted: (T::NFTId, u128),
+	/// The only account allowed to accept the swap, anyone if `None`
+	pub counterparty: Option<T::AccountId>,
+	/// Currency given by the proposer on top of the offered NFT, reserved until the swap ends
+	pub top_up: Option<BalanceOf<T>>,
+}
@@ -8,7 +8,20 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use sp_std::vec::Vec;
+pub mod runtime_api;
+pub mod types;
+
+use frame_support::{
+	dispatch::{DispatchClass, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::{Dispatchable, One, Zero},
+	traits::{Currency, ReservableCurrency, Time},
+};
+use sp_std::{boxed::Box, vec::Vec};
+use types::*;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -22,76 +35,742 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type Currency: ReservableCurrency<Self::AccountId>;
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo;
+
+		#[pallet::constant]
+		type MaxMessageLen: Get<u32>;
+
+		// Maximum length of an encoded scheduled call
+		#[pallet::constant]
+		type MaxCallLen: Get<u32>;
+
+		// Bounds the number of reminders, and so the weight, of `on_initialize`
+		#[pallet::constant]
+		type MaxRemindersPerBlock: Get<u32>;
+
+		// Maximum weight spent executing reminders in `on_initialize`, leftovers are deferred
+		#[pallet::constant]
+		type ReminderWeightLimit: Get<Weight>;
+
+		// Number of executed reminders kept in the history of each account
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
+
+		// Bounds the number of blocks, and so the storage reads, walked by `upcoming`
+		#[pallet::constant]
+		type MaxUpcomingBlocks: Get<u32>;
+
+		// Wall-clock time, usually `pallet_timestamp`
+		type Time: Time;
+
+		// Length of the time buckets timed reminders are grouped by, must not be zero
+		#[pallet::constant]
+		type TimestampBucket: Get<MomentOf<Self>>;
+
+		// Bounds the number of time buckets, and so the weight, executed in `on_finalize`
+		#[pallet::constant]
+		type MaxBucketsPerBlock: Get<u32>;
+
+		// Bounds the number of time buckets holding timed reminders at once
+		#[pallet::constant]
+		type MaxPendingBuckets: Get<u32>;
+
+		// Reserved from the owner of a reminder until it fires or is cancelled
+		#[pallet::constant]
+		type ReminderDeposit: Get<BalanceOf<Self>>;
 	}
 
 	#[pallet::storage]
 	#[pallet::getter(fn event_counter)]
 	pub type EventCounter<T> = StorageValue<_, u32, ValueQuery>;
 
+	/// Number of reminders executed since genesis
+	#[pallet::storage]
+	#[pallet::getter(fn total_executed)]
+	pub type TotalExecuted<T> = StorageValue<_, u64, ValueQuery>;
+
+	/// Last reminders executed for each account, most recent first: (reminder_id, executed_at)
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(ReminderId, BlockNumberFor<T>), T::MaxHistory>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_reminder_id)]
+	pub type NextReminderId<T> = StorageValue<_, ReminderId, ValueQuery>;
+
+	/// Oldest block whose reminders have not all been executed yet
+	#[pallet::storage]
+	#[pallet::getter(fn incomplete)]
+	pub type Incomplete<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+	/// Where a named reminder is scheduled: (at, reminder_id)
+	#[pallet::storage]
+	#[pallet::getter(fn lookup)]
+	pub type Lookup<T: Config> =
+		StorageMap<_, Blake2_128Concat, TaskName, (BlockNumberFor<T>, ReminderId), OptionQuery>;
+
 	#[pallet::storage]
-	#[pallet::unbounded]
 	#[pallet::getter(fn reminders)]
-	pub type Reminders<T: Config> =
-		StorageMap<_, Blake2_256, BlockNumberFor<T>, Vec<Vec<u8>>, ValueQuery>;
+	pub type Reminders<T: Config> = StorageMap<
+		_,
+		Blake2_256,
+		BlockNumberFor<T>,
+		BoundedVec<ReminderData<T>, T::MaxRemindersPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Timed reminders by bucket: (moment, reminder)
+	#[pallet::storage]
+	#[pallet::getter(fn timed_reminders)]
+	pub type TimedReminders<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		MomentOf<T>,
+		BoundedVec<(MomentOf<T>, ReminderData<T>), T::MaxRemindersPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Time buckets holding timed reminders, earliest first, so empty ones are never walked
+	#[pallet::storage]
+	#[pallet::getter(fn pending_buckets)]
+	pub type PendingBuckets<T: Config> =
+		StorageValue<_, BoundedVec<MomentOf<T>, T::MaxPendingBuckets>, ValueQuery>;
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		ReminderSet(BlockNumberFor<T>, Vec<u8>),
-		Reminder(Vec<u8>),
+		/// (reminder_id, owner, at, message)
+		ReminderSet(ReminderId, T::AccountId, BlockNumberFor<T>, Vec<u8>),
+		/// (reminder_id, owner, message)
+		Reminder(ReminderId, T::AccountId, Vec<u8>),
+		/// (reminder_id, owner)
+		ReminderCancelled(ReminderId, T::AccountId),
+		/// (reminder_id, owner, start, period, repetitions, message)
+		RecurringReminderSet(
+			ReminderId,
+			T::AccountId,
+			BlockNumberFor<T>,
+			BlockNumberFor<T>,
+			u32,
+			Vec<u8>,
+		),
+		/// A recurring reminder could not be rescheduled because the target block was full
+		/// (reminder_id, owner)
+		RecurrenceStopped(ReminderId, T::AccountId),
+		/// The weight limit was reached, reminders from this block on will run later
+		/// (incomplete_since)
+		RemindersDeferred(BlockNumberFor<T>),
+		/// (reminder_id, owner, at, priority, name)
+		CallScheduled(
+			ReminderId,
+			T::AccountId,
+			BlockNumberFor<T>,
+			Priority,
+			Option<TaskName>,
+		),
+		/// (reminder_id, owner, moment, message)
+		TimedReminderSet(ReminderId, T::AccountId, MomentOf<T>, Vec<u8>),
+		/// (reminder_id, owner, result)
+		Dispatched(ReminderId, T::AccountId, DispatchResult),
 		RemindersExecuteds(u32),
 	}
 
 	#[pallet::error]
-	pub enum Error<T> {}
+	pub enum Error<T> {
+		ReminderNotFound,
+		NotReminderOwner,
+		Overflow,
+		MessageTooLong,
+		ReminderInThePast,
+		TooManyReminders,
+		ZeroPeriod,
+		ZeroRepetitions,
+		CallTooLong,
+		CallUndecodable,
+		NameTaken,
+		CallTooHeavy,
+	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		// on_initialize() will be called at the beginning of each new block, before anything
+		//
+		// Reminders are executed within `ReminderWeightLimit`, oldest block first. Whatever
+		// does not fit stays in its agenda and `Incomplete` points to it for the next blocks.
 		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-			let mut used_weight: Weight = 0.into();
-
-			let reminders = Self::reminders(n);
+			let db_weight = T::DbWeight::get();
+			let limit = T::ReminderWeightLimit::get();
+			// Keep room for the final writes of `TotalExecuted` and `Incomplete`
+			let fits = |weight: Weight| (weight + db_weight.reads_writes(1, 2)).all_lte(limit);
 
 			// this is an example of how do we get system weights for read and writes.
 			// you only have to mesure read and writes for this exercice !
-			//
-			// try to do this hook in one read and two writes !
-			used_weight += T::DbWeight::get().reads(1);
+			let incomplete = Self::incomplete();
+			let mut used_weight = db_weight.reads(1);
+
+			// `EventCounter` is written whatever happens
+			used_weight += db_weight.writes(1);
 
-			let event_count = reminders.len() as u32;
+			let mut block = incomplete.unwrap_or(n);
+			let mut event_count = 0u32;
+			while block <= n && fits(used_weight + db_weight.reads_writes(1, 1)) {
+				let reminders = Reminders::<T>::take(block);
+				used_weight += db_weight.reads_writes(1, 1);
 
-			EventCounter::<T>::mutate(|value| *value = event_count);
-			used_weight += T::DbWeight::get().writes(1);
+				let mut leftovers = Vec::new();
+				for reminder in reminders {
+					if leftovers.is_empty()
+						&& fits(used_weight + Self::max_execution_weight(&reminder))
+					{
+						used_weight += Self::execute(n, reminder);
+						event_count += 1;
+					} else {
+						leftovers.push(reminder);
+					}
+				}
 
-			for reminder in reminders {
-				Self::deposit_event(Event::Reminder(reminder));
+				if !leftovers.is_empty() {
+					// Taken from a bounded agenda, so it still fits
+					let leftovers = BoundedVec::truncate_from(leftovers);
+					Reminders::<T>::insert(block, leftovers);
+					break
+				}
+				block += One::one();
 			}
 
-			Reminders::<T>::remove(n);
-			used_weight += T::DbWeight::get().writes(1);
+			EventCounter::<T>::put(event_count);
+			if event_count > 0 {
+				TotalExecuted::<T>::mutate(|total| {
+					*total = total.saturating_add(event_count.into())
+				});
+				used_weight += db_weight.reads_writes(1, 1);
+			}
+
+			if block <= n {
+				Incomplete::<T>::put(block);
+				used_weight += db_weight.writes(1);
+				Self::deposit_event(Event::RemindersDeferred(block));
+			} else if incomplete.is_some() {
+				Incomplete::<T>::kill();
+				used_weight += db_weight.writes(1);
+			}
 
 			used_weight
 		}
 
+		// The timestamp of the block is only known once its inherent is applied, so timed
+		// reminders are executed here and their weight registered afterwards
 		fn on_finalize(n: BlockNumberFor<T>) {
+			let used_weight = Self::execute_timed(n);
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				used_weight,
+				DispatchClass::Mandatory,
+			);
+
 			let count = Self::event_counter();
 			Self::deposit_event(Event::RemindersExecuteds(count));
 		}
+
+		fn integrity_test() {
+			assert!(
+				!T::TimestampBucket::get().is_zero(),
+				"`TimestampBucket` must not be zero"
+			);
+			assert!(
+				T::MaxBucketsPerBlock::get() > 0,
+				"`MaxBucketsPerBlock` must not be zero"
+			);
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads(1))]
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(3, 3))]
 		pub fn schedule_reminder(
 			origin: OriginFor<T>,
 			at: BlockNumberFor<T>,
 			message: Vec<u8>,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let owner = ensure_signed(origin)?;
+
+			let id = Self::do_schedule(
+				&owner,
+				at,
+				LOWEST_PRIORITY,
+				None,
+				message.clone(),
+				None,
+				None,
+			)?;
+			Self::deposit_event(Event::ReminderSet(id, owner, at, message));
+
+			Ok(())
+		}
+
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn schedule_recurring(
+			origin: OriginFor<T>,
+			start: BlockNumberFor<T>,
+			period: BlockNumberFor<T>,
+			repetitions: u32,
+			message: Vec<u8>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			ensure!(!period.is_zero(), Error::<T>::ZeroPeriod);
+			ensure!(repetitions > 0, Error::<T>::ZeroRepetitions);
+
+			let recurrence = Recurrence {
+				period,
+				remaining: repetitions,
+			};
+			let id = Self::do_schedule(
+				&owner,
+				start,
+				LOWEST_PRIORITY,
+				None,
+				message.clone(),
+				Some(recurrence),
+				None,
+			)?;
+			Self::deposit_event(Event::RecurringReminderSet(
+				id,
+				owner,
+				start,
+				period,
+				repetitions,
+				message,
+			));
+
+			Ok(())
+		}
+
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(2, 3))]
+		pub fn cancel_reminder(
+			origin: OriginFor<T>,
+			at: BlockNumberFor<T>,
+			id: ReminderId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_cancel(&who, at, id)
+		}
+
+		/// Dispatches `call` at block `at` with the caller as signed origin
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(4, 4))]
+		pub fn schedule_call(
+			origin: OriginFor<T>,
+			at: BlockNumberFor<T>,
+			priority: Priority,
+			name: Option<TaskName>,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			let scheduled = ScheduledCall {
+				weight: call.get_dispatch_info().weight,
+				encoded: call.encode().try_into().map_err(|_| Error::<T>::CallTooLong)?,
+			};
+			let id = Self::do_schedule(
+				&owner,
+				at,
+				priority,
+				name,
+				Vec::new(),
+				None,
+				Some(scheduled),
+			)?;
+			Self::deposit_event(Event::CallScheduled(id, owner, at, priority, name));
+
+			Ok(())
+		}
+
+		/// Fires in the first block whose timestamp is past `moment`
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(4, 4))]
+		pub fn schedule_at_timestamp(
+			origin: OriginFor<T>,
+			moment: MomentOf<T>,
+			message: Vec<u8>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			ensure!(moment > T::Time::now(), Error::<T>::ReminderInThePast);
+
+			let reminder =
+				Self::new_reminder(&owner, message.clone(), LOWEST_PRIORITY, None, None, None)?;
+			let id = reminder.id;
+
+			let bucket = moment / T::TimestampBucket::get();
+			<TimedReminders<T>>::try_mutate(bucket, |reminders| -> DispatchResult {
+				if reminders.is_empty() {
+					PendingBuckets::<T>::try_mutate(|buckets| {
+						let index = buckets.partition_point(|pending| *pending < bucket);
+						buckets.try_insert(index, bucket)
+					})
+					.map_err(|_| Error::<T>::TooManyReminders)?;
+				}
+				reminders
+					.try_push((moment, reminder))
+					.map_err(|_| Error::<T>::TooManyReminders)?;
+
+				Ok(())
+			})?;
+			Self::deposit_event(Event::TimedReminderSet(id, owner, moment, message));
+
+			Ok(())
+		}
+
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn cancel_named(origin: OriginFor<T>, name: TaskName) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (at, id) = Self::lookup(name).ok_or(Error::<T>::ReminderNotFound)?;
+			Self::do_cancel(&who, at, id)
+		}
+
+		#[pallet::weight(Weight::from(10_000) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn cancel_timed(
+			origin: OriginFor<T>,
+			moment: MomentOf<T>,
+			id: ReminderId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_cancel_timed(&who, moment, id)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Weight of `execute` in the worst case: a named recurring reminder that cannot be
+		/// rescheduled
+		fn max_execution_weight(reminder: &ReminderData<T>) -> Weight {
+			let call_weight = reminder.call.as_ref().map_or(Weight::zero(), |call| call.weight);
+			T::DbWeight::get().reads_writes(3, 4) + call_weight
+		}
+
+		/// Whether `on_initialize` can execute `reminder` when it is the only one in its budget
+		fn fits_alone(reminder: &ReminderData<T>) -> bool {
+			// `Incomplete`, `EventCounter` and the agenda, then `TotalExecuted` and `Incomplete`
+			let hook_weight = T::DbWeight::get().reads_writes(3, 4);
+			(hook_weight + Self::max_execution_weight(reminder))
+				.all_lte(T::ReminderWeightLimit::get())
+		}
+
+		/// Emits the reminder or dispatches its call, then either reschedules it or refunds its
+		/// deposit
+		fn execute(n: BlockNumberFor<T>, reminder: ReminderData<T>) -> Weight {
+			let mut used_weight: Weight = 0.into();
+			let (id, owner, deposit, name) = (
+				reminder.id,
+				reminder.owner.clone(),
+				reminder.deposit,
+				reminder.name,
+			);
+
+			match &reminder.call {
+				Some(call) => used_weight += Self::dispatch(id, &owner, call),
+				None => Self::deposit_event(Event::Reminder(
+					id,
+					owner.clone(),
+					reminder.message.clone().into_inner(),
+				)),
+			}
+
+			History::<T>::mutate(&owner, |history| {
+				// Drops the oldest entry once full
+				let _ = history.force_insert_keep_left(0, (id, n));
+			});
+			used_weight += T::DbWeight::get().reads_writes(1, 1);
+
+			// A recurring reminder keeps its deposit until its last occurrence
+			if let Some(recurrence) = reminder.recurrence.filter(|r| r.remaining > 1) {
+				let next = n.saturating_add(recurrence.period);
+				let rescheduled = Reminders::<T>::try_mutate(next, |reminders| {
+					Self::insert_by_priority(
+						reminders,
+						ReminderData {
+							recurrence: Some(Recurrence {
+								remaining: recurrence.remaining - 1,
+								..recurrence
+							}),
+							..reminder
+						},
+					)
+				});
+				used_weight += T::DbWeight::get().reads_writes(1, 1);
+
+				if rescheduled.is_ok() {
+					if let Some(name) = name {
+						Lookup::<T>::insert(name, (next, id));
+						used_weight += T::DbWeight::get().writes(1);
+					}
+					return used_weight
+				}
+				Self::deposit_event(Event::RecurrenceStopped(id, owner.clone()));
+			}
+
+			T::Currency::unreserve(&owner, deposit);
+			used_weight += T::DbWeight::get().reads_writes(1, 1);
+			if let Some(name) = name {
+				Lookup::<T>::remove(name);
+				used_weight += T::DbWeight::get().writes(1);
+			}
+
+			used_weight
+		}
+
+		/// Executes the timed reminders whose moment is past, from at most `MaxBucketsPerBlock`
+		/// buckets
+		fn execute_timed(n: BlockNumberFor<T>) -> Weight {
+			let db_weight = T::DbWeight::get();
+			let buckets = Self::pending_buckets();
+			let now = T::Time::now();
+			let current = now / T::TimestampBucket::get();
+			let mut used_weight = db_weight.reads(2);
+
+			let mut executed = 0u32;
+			let mut emptied = 0;
+			for &bucket in buckets
+				.iter()
+				.take(T::MaxBucketsPerBlock::get() as usize)
+				.take_while(|bucket| **bucket <= current)
+			{
+				let reminders = TimedReminders::<T>::take(bucket);
+				used_weight += db_weight.reads_writes(1, 1);
+
+				// Only the current bucket can hold reminders which are not due yet
+				let mut pending = BoundedVec::new();
+				for (moment, reminder) in reminders {
+					if moment < now {
+						used_weight += Self::execute(n, reminder);
+						executed += 1;
+					} else {
+						let _ = pending.try_push((moment, reminder));
+					}
+				}
+				if pending.is_empty() {
+					emptied += 1;
+				} else {
+					TimedReminders::<T>::insert(bucket, pending);
+				}
+			}
+
+			// Buckets are walked in order and only the current one can be left, so the emptied
+			// ones come first
+			if emptied > 0 {
+				PendingBuckets::<T>::put(BoundedVec::truncate_from(buckets[emptied..].to_vec()));
+				used_weight += db_weight.writes(1);
+			}
+
+			if executed > 0 {
+				EventCounter::<T>::mutate(|count| *count += executed);
+				TotalExecuted::<T>::mutate(|total| *total = total.saturating_add(executed.into()));
+				used_weight += db_weight.reads_writes(2, 2);
+			}
+
+			used_weight
+		}
+
+		/// Reminders of `who` scheduled from block `from` to block `to`, both included. Blocks
+		/// more than `MaxUpcomingBlocks` after `from` are left out.
+		pub fn upcoming(
+			who: &T::AccountId,
+			from: BlockNumberFor<T>,
+			to: BlockNumberFor<T>,
+		) -> Vec<(BlockNumberFor<T>, ReminderId)> {
+			let to = to.min(from.saturating_add(T::MaxUpcomingBlocks::get().into()));
+			let mut upcoming = Vec::new();
+			let mut block = from;
+			while block <= to {
+				upcoming.extend(
+					Self::reminders(block)
+						.into_iter()
+						.filter(|reminder| reminder.owner == *who)
+						.map(|reminder| (block, reminder.id)),
+				);
+				if block == to {
+					break
+				}
+				block += One::one();
+			}
+
+			upcoming
+		}
+
+		/// Timed reminders of `who` due from moment `from` to moment `to`, both included
+		pub fn upcoming_timed(
+			who: &T::AccountId,
+			from: MomentOf<T>,
+			to: MomentOf<T>,
+		) -> Vec<(MomentOf<T>, ReminderId)> {
+			let (first, last) = (
+				from / T::TimestampBucket::get(),
+				to / T::TimestampBucket::get(),
+			);
+			Self::pending_buckets()
+				.into_iter()
+				.filter(|bucket| first <= *bucket && *bucket <= last)
+				.flat_map(Self::timed_reminders)
+				.filter(|(moment, reminder)| {
+					reminder.owner == *who && from <= *moment && *moment <= to
+				})
+				.map(|(moment, reminder)| (moment, reminder.id))
+				.collect()
+		}
+
+		fn dispatch(id: ReminderId, owner: &T::AccountId, call: &ScheduledCall<T>) -> Weight {
+			let (result, actual_weight) =
+				match <<T as Config>::RuntimeCall as Decode>::decode(&mut &call.encoded[..]) {
+					Ok(runtime_call) => {
+						let origin = frame_system::RawOrigin::Signed(owner.clone()).into();
+						match runtime_call.dispatch(origin) {
+							Ok(post_info) => (Ok(()), post_info.actual_weight),
+							Err(error) => (Err(error.error), error.post_info.actual_weight),
+						}
+					},
+					// The runtime changed since the call was scheduled
+					Err(_) => (
+						Err(Error::<T>::CallUndecodable.into()),
+						Some(Weight::zero()),
+					),
+				};
+			Self::deposit_event(Event::Dispatched(id, owner.clone(), result));
+
+			actual_weight.unwrap_or(call.weight)
+		}
+
+		fn insert_by_priority(
+			reminders: &mut BoundedVec<ReminderData<T>, T::MaxRemindersPerBlock>,
+			reminder: ReminderData<T>,
+		) -> Result<(), ReminderData<T>> {
+			let index = reminders.partition_point(|r| r.priority <= reminder.priority);
+			reminders.try_insert(index, reminder)
+		}
+
+		fn do_schedule(
+			owner: &T::AccountId,
+			at: BlockNumberFor<T>,
+			priority: Priority,
+			name: Option<TaskName>,
+			message: Vec<u8>,
+			recurrence: Option<Recurrence<BlockNumberFor<T>>>,
+			call: Option<ScheduledCall<T>>,
+		) -> Result<ReminderId, DispatchError> {
+			// Reminders of the current block have already been executed
+			ensure!(
+				at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::ReminderInThePast
+			);
+			if let Some(name) = name {
+				ensure!(!Lookup::<T>::contains_key(name), Error::<T>::NameTaken);
+			}
+
+			let reminder = Self::new_reminder(owner, message, priority, name, recurrence, call)?;
+			// It would never be executed and would hold up every reminder scheduled after it
+			ensure!(Self::fits_alone(&reminder), Error::<T>::CallTooHeavy);
+
+			let id = reminder.id;
+			if let Some(name) = name {
+				Lookup::<T>::insert(name, (at, id));
+			}
+
+			<Reminders<T>>::try_mutate(at, |reminders| {
+				Self::insert_by_priority(reminders, reminder)
+					.map_err(|_| Error::<T>::TooManyReminders)
+			})?;
+
+			Ok(id)
+		}
+
+		/// Bounds the message, allocates the id and reserves the deposit of a new reminder
+		fn new_reminder(
+			owner: &T::AccountId,
+			message: Vec<u8>,
+			priority: Priority,
+			name: Option<TaskName>,
+			recurrence: Option<Recurrence<BlockNumberFor<T>>>,
+			call: Option<ScheduledCall<T>>,
+		) -> Result<ReminderData<T>, DispatchError> {
+			let bounded_message: BoundedVec<u8, T::MaxMessageLen> =
+				message.try_into().map_err(|_| Error::<T>::MessageTooLong)?;
+
+			let id = Self::next_reminder_id();
+			NextReminderId::<T>::put(id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+
+			let deposit = T::ReminderDeposit::get();
+			T::Currency::reserve(owner, deposit)?;
+
+			Ok(ReminderData {
+				id,
+				owner: owner.clone(),
+				message: bounded_message,
+				deposit,
+				recurrence,
+				priority,
+				name,
+				call,
+			})
+		}
+
+		fn do_cancel(who: &T::AccountId, at: BlockNumberFor<T>, id: ReminderId) -> DispatchResult {
+			<Reminders<T>>::try_mutate(at, |reminders| -> DispatchResult {
+				let index = reminders
+					.iter()
+					.position(|reminder| reminder.id == id)
+					.ok_or(Error::<T>::ReminderNotFound)?;
+				ensure!(reminders[index].owner == *who, Error::<T>::NotReminderOwner);
+
+				let reminder = reminders.remove(index);
+				T::Currency::unreserve(who, reminder.deposit);
+				if let Some(name) = reminder.name {
+					Lookup::<T>::remove(name);
+				}
+
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ReminderCancelled(id, who.clone()));
+
+			Ok(())
+		}
+
+		fn do_cancel_timed(
+			who: &T::AccountId,
+			moment: MomentOf<T>,
+			id: ReminderId,
+		) -> DispatchResult {
+			let bucket = moment / T::TimestampBucket::get();
+			<TimedReminders<T>>::try_mutate(bucket, |reminders| -> DispatchResult {
+				let index = reminders
+					.iter()
+					.position(|(_, reminder)| reminder.id == id)
+					.ok_or(Error::<T>::ReminderNotFound)?;
+				ensure!(
+					reminders[index].1.owner == *who,
+					Error::<T>::NotReminderOwner
+				);
+
+				let (_, reminder) = reminders.remove(index);
+				T::Currency::unreserve(who, reminder.deposit);
+				if reminders.is_empty() {
+					PendingBuckets::<T>::mutate(|buckets| {
+						buckets.retain(|pending| *pending != bucket)
+					});
+				}
 
-			<Reminders<T>>::mutate(at, |reminders| reminders.push(message.clone()));
-			Self::deposit_event(Event::ReminderSet(at, message));
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ReminderCancelled(id, who.clone()));
 
 			Ok(())
 		}
@@ -2,7 +2,7 @@ use crate as pallet_reminder;
 use frame_support::{
 	derive_impl, parameter_types,
 	traits::{ConstU16, ConstU64},
-	weights::RuntimeDbWeight,
+	weights::{RuntimeDbWeight, Weight},
 };
 use sp_core::H256;
 use sp_runtime::{
@@ -19,6 +19,8 @@ frame_support::construct_runtime!(
 	pub enum TestRuntime
 	{
 		System: frame_system,
+		Balances: pallet_balances,
+		Timestamp: pallet_timestamp,
 		Reminder: pallet_reminder,
 	}
 );
@@ -29,7 +31,7 @@ parameter_types! {
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
 impl frame_system::Config for TestRuntime {
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type AccountId = u64;
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockHashCount = ConstU64<250>;
@@ -53,12 +55,75 @@ impl frame_system::Config for TestRuntime {
 	type Version = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
+	pub const MaxFreezes: u32 = 100;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type AccountStore = System;
+	type Balance = u128;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = MaxFreezes;
+}
+
+impl pallet_timestamp::Config for TestRuntime {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaxMessageLen: u32 = 16;
+	pub const MaxCallLen: u32 = 128;
+	pub const MaxRemindersPerBlock: u32 = 3;
+	pub const MaxHistory: u32 = 2;
+	pub const MaxUpcomingBlocks: u32 = 10;
+	pub const TimestampBucket: u64 = 1000;
+	pub const MaxBucketsPerBlock: u32 = 4;
+	pub const MaxPendingBuckets: u32 = 8;
+	pub const ReminderDeposit: u128 = 100;
+	pub static ReminderWeightLimit: Weight = Weight::from_parts(1_000_000_000, 1_000_000);
+}
+
 impl pallet_reminder::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type RuntimeCall = RuntimeCall;
+	type MaxMessageLen = MaxMessageLen;
+	type MaxCallLen = MaxCallLen;
+	type MaxRemindersPerBlock = MaxRemindersPerBlock;
+	type ReminderWeightLimit = ReminderWeightLimit;
+	type MaxHistory = MaxHistory;
+	type MaxUpcomingBlocks = MaxUpcomingBlocks;
+	type Time = Timestamp;
+	type TimestampBucket = TimestampBucket;
+	type MaxBucketsPerBlock = MaxBucketsPerBlock;
+	type MaxPendingBuckets = MaxPendingBuckets;
+	type ReminderDeposit = ReminderDeposit;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	let t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
+	let mut t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
+
+	pallet_balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(|| System::set_block_number(1));
 	ext
@@ -66,3 +131,6 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
 // Mock users AccountId
 pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+
+pub const INITIAL_BALANCE: u128 = 1000;
@@ -0,0 +1,20 @@
+//! Runtime API to query the reminders of an account.
+
+use crate::types::ReminderId;
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait ReminderApi<AccountId, BlockNumber, Moment>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+	{
+		/// The reminders of `who` scheduled from block `from` to block `to`, at most
+		/// `MaxUpcomingBlocks` blocks after `from`: (at, reminder_id)
+		fn upcoming(who: AccountId, from: BlockNumber, to: BlockNumber) -> Vec<(BlockNumber, ReminderId)>;
+		/// The timed reminders of `who` due from `from` to `to`: (moment, reminder_id)
+		fn upcoming_timed(who: AccountId, from: Moment, to: Moment) -> Vec<(Moment, ReminderId)>;
+	}
+}
@@ -1,10 +1,15 @@
-use crate::mock::*;
-use frame_support::assert_ok;
+use crate::{
+	mock::*,
+	types::{Recurrence, ReminderData, HIGHEST_PRIORITY, LOWEST_PRIORITY},
+	Error,
+};
+use frame_support::{assert_noop, assert_ok};
 
 use frame_support::{
 	traits::{OnFinalize, OnInitialize},
-	weights::RuntimeDbWeight,
+	weights::{RuntimeDbWeight, Weight},
 };
+use sp_runtime::DispatchError;
 
 mod mint {
 	use super::*;
@@ -14,15 +19,29 @@ mod mint {
 		new_test_ext().execute_with(|| {
 			assert_ok!(Reminder::schedule_reminder(
 				RuntimeOrigin::signed(ALICE),
-				1,
+				2,
 				"test".as_bytes().to_vec(),
 			));
 			assert_eq!(
-				Reminder::reminders(1),
+				Reminder::reminders(2).into_inner(),
 				vec! {
-					"test".as_bytes().to_vec()
+					ReminderData {
+						id: 0,
+						owner: ALICE,
+						message: "test".as_bytes().to_vec().try_into().unwrap(),
+						deposit: ReminderDeposit::get(),
+						recurrence: None,
+						priority: LOWEST_PRIORITY,
+						name: None,
+						call: None,
+					}
 				}
-			)
+			);
+			assert_eq!(Reminder::next_reminder_id(), 1);
+			assert_eq!(Balances::reserved_balance(ALICE), ReminderDeposit::get());
+			System::assert_last_event(
+				crate::Event::ReminderSet(0, ALICE, 2, "test".as_bytes().to_vec()).into(),
+			);
 		})
 	}
 
@@ -40,11 +59,16 @@ mod mint {
 				"test2".as_bytes().to_vec(),
 			));
 			<Reminder as OnInitialize<u64>>::on_initialize(2);
-			System::assert_last_event(crate::Event::Reminder("test2".as_bytes().to_vec()).into());
-			System::assert_has_event(crate::Event::Reminder("test".as_bytes().to_vec()).into());
+			System::assert_has_event(
+				crate::Event::Reminder(1, ALICE, "test2".as_bytes().to_vec()).into(),
+			);
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
 
 			//check if events have been removed from the storage after being emitted
-			assert_eq!(Reminder::reminders(2), <Vec<Vec<u8>>>::new())
+			assert!(Reminder::reminders(2).is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
 		})
 	}
 
@@ -105,11 +129,757 @@ mod mint {
 				2,
 				"test2".as_bytes().to_vec(),
 			));
+			// Three reads and three writes, plus the history and the refund of each reminder
 			assert_eq!(
 				<Reminder as OnInitialize<u64>>::on_initialize(2),
-				db_weights.reads_writes(1, 2)
+				db_weights.reads_writes(3 + 2 * 2, 3 + 2 * 2)
+			);
+			<Reminder as OnFinalize<u64>>::on_finalize(2);
+		})
+	}
+}
+
+mod cancel {
+	use super::*;
+
+	#[test]
+	fn ok() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test".as_bytes().to_vec(),
+			));
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test2".as_bytes().to_vec(),
+			));
+
+			assert_ok!(Reminder::cancel_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				0
+			));
+
+			assert_eq!(Reminder::reminders(2).len(), 1);
+			assert_eq!(Reminder::reminders(2)[0].id, 1);
+			assert_eq!(Balances::reserved_balance(ALICE), ReminderDeposit::get());
+			System::assert_last_event(crate::Event::ReminderCancelled(0, ALICE).into());
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Reminder::event_counter(), 1);
+		})
+	}
+
+	#[test]
+	fn not_owner() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test".as_bytes().to_vec(),
+			));
+
+			assert_noop!(
+				Reminder::cancel_reminder(RuntimeOrigin::signed(BOB), 2, 0),
+				Error::<TestRuntime>::NotReminderOwner
+			);
+		})
+	}
+
+	#[test]
+	fn not_found() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test".as_bytes().to_vec(),
+			));
+
+			assert_noop!(
+				Reminder::cancel_reminder(RuntimeOrigin::signed(ALICE), 3, 0),
+				Error::<TestRuntime>::ReminderNotFound
+			);
+			assert_noop!(
+				Reminder::cancel_reminder(RuntimeOrigin::signed(ALICE), 2, 1),
+				Error::<TestRuntime>::ReminderNotFound
+			);
+		})
+	}
+}
+
+mod recurring {
+	use super::*;
+
+	#[test]
+	fn schedule() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_recurring(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				3,
+				2,
+				"test".as_bytes().to_vec(),
+			));
+			assert_eq!(
+				Reminder::reminders(2)[0].recurrence,
+				Some(Recurrence {
+					period: 3,
+					remaining: 2
+				})
+			);
+			assert_eq!(Balances::reserved_balance(ALICE), ReminderDeposit::get());
+			System::assert_last_event(
+				crate::Event::RecurringReminderSet(0, ALICE, 2, 3, 2, "test".as_bytes().to_vec())
+					.into(),
+			);
+		})
+	}
+
+	#[test]
+	fn fires_until_exhausted() {
+		new_test_ext().execute_with(|| {
+			let db_weights: RuntimeDbWeight =
+				<TestRuntime as frame_system::Config>::DbWeight::get();
+
+			assert_ok!(Reminder::schedule_recurring(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				3,
+				2,
+				"test".as_bytes().to_vec(),
+			));
+
+			// Three reads and three writes, plus the history and the rescheduling
+			assert_eq!(
+				<Reminder as OnInitialize<u64>>::on_initialize(2),
+				db_weights.reads_writes(3 + 2, 3 + 2)
+			);
+			System::assert_last_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert!(Reminder::reminders(2).is_empty());
+			assert_eq!(
+				Reminder::reminders(5)[0].recurrence,
+				Some(Recurrence {
+					period: 3,
+					remaining: 1
+				})
+			);
+			assert_eq!(Balances::reserved_balance(ALICE), ReminderDeposit::get());
+
+			// Three reads and three writes, plus the history and the refund of the deposit
+			assert_eq!(
+				<Reminder as OnInitialize<u64>>::on_initialize(5),
+				db_weights.reads_writes(3 + 2, 3 + 2)
+			);
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert!(Reminder::reminders(5).is_empty());
+			assert!(Reminder::reminders(8).is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn stopped_when_next_block_is_full() {
+		new_test_ext().execute_with(|| {
+			let db_weights: RuntimeDbWeight =
+				<TestRuntime as frame_system::Config>::DbWeight::get();
+
+			assert_ok!(Reminder::schedule_recurring(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				3,
+				2,
+				"test".as_bytes().to_vec(),
+			));
+			for _ in 0..MaxRemindersPerBlock::get() {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(BOB),
+					5,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			// Three reads and three writes, plus the history, the failed rescheduling and the
+			// refund
+			assert_eq!(
+				<Reminder as OnInitialize<u64>>::on_initialize(2),
+				db_weights.reads_writes(3 + 3, 3 + 3)
+			);
+			System::assert_has_event(crate::Event::RecurrenceStopped(0, ALICE).into());
+			assert!(Reminder::reminders(5).iter().all(|reminder| reminder.owner == BOB));
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn invalid_recurrence() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Reminder::schedule_recurring(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					0,
+					2,
+					"test".as_bytes().to_vec(),
+				),
+				Error::<TestRuntime>::ZeroPeriod
+			);
+			assert_noop!(
+				Reminder::schedule_recurring(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					3,
+					0,
+					"test".as_bytes().to_vec(),
+				),
+				Error::<TestRuntime>::ZeroRepetitions
+			);
+		})
+	}
+}
+
+mod deferred {
+	use super::*;
+
+	#[test]
+	fn carried_over() {
+		new_test_ext().execute_with(|| {
+			// Enough for a single reminder per block
+			ReminderWeightLimit::set(Weight::from_parts(90_000, 0));
+			for _ in 0..3 {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			System::assert_last_event(crate::Event::RemindersDeferred(2).into());
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert_eq!(Reminder::event_counter(), 1);
+			assert_eq!(Reminder::incomplete(), Some(2));
+			assert_eq!(Reminder::reminders(2).len(), 2);
+
+			<Reminder as OnInitialize<u64>>::on_initialize(3);
+			System::assert_has_event(
+				crate::Event::Reminder(1, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert_eq!(Reminder::event_counter(), 1);
+			assert_eq!(Reminder::reminders(2).len(), 1);
+
+			<Reminder as OnInitialize<u64>>::on_initialize(4);
+			System::assert_has_event(
+				crate::Event::Reminder(2, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert_eq!(Reminder::event_counter(), 1);
+			assert!(Reminder::reminders(2).is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+			// Empty agendas left behind are caught up with
+			<Reminder as OnInitialize<u64>>::on_initialize(5);
+			assert_eq!(Reminder::incomplete(), None);
+		})
+	}
+
+	#[test]
+	fn within_limit() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..3 {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Reminder::event_counter(), 3);
+			assert_eq!(Reminder::incomplete(), None);
+		})
+	}
+}
+
+mod call {
+	use super::*;
+
+	fn remark() -> Box<RuntimeCall> {
+		Box::new(RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: vec![1],
+		}))
+	}
+
+	#[test]
+	fn dispatched() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_call(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				HIGHEST_PRIORITY,
+				None,
+				remark(),
+			));
+			System::assert_last_event(crate::Event::CallScheduled(0, ALICE, 2, 0, None).into());
+			assert_eq!(Balances::reserved_balance(ALICE), ReminderDeposit::get());
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			System::assert_has_event(crate::Event::Dispatched(0, ALICE, Ok(())).into());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn failure_reported() {
+		new_test_ext().execute_with(|| {
+			// Requires root
+			let call = RuntimeCall::System(frame_system::Call::set_heap_pages { pages: 1 });
+			assert_ok!(Reminder::schedule_call(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				HIGHEST_PRIORITY,
+				None,
+				Box::new(call),
+			));
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			System::assert_has_event(
+				crate::Event::Dispatched(0, ALICE, Err(DispatchError::BadOrigin)).into(),
+			);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn by_priority() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test".as_bytes().to_vec(),
+			));
+			assert_ok!(Reminder::schedule_call(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				HIGHEST_PRIORITY,
+				None,
+				remark(),
+			));
+
+			assert_eq!(Reminder::reminders(2)[0].id, 1);
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+		})
+	}
+
+	#[test]
+	fn named() {
+		new_test_ext().execute_with(|| {
+			let name = [1; 32];
+			assert_ok!(Reminder::schedule_call(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				HIGHEST_PRIORITY,
+				Some(name),
+				remark(),
+			));
+			assert_eq!(Reminder::lookup(name), Some((2, 0)));
+			assert_noop!(
+				Reminder::schedule_call(
+					RuntimeOrigin::signed(BOB),
+					3,
+					HIGHEST_PRIORITY,
+					Some(name),
+					remark(),
+				),
+				Error::<TestRuntime>::NameTaken
+			);
+			assert_noop!(
+				Reminder::cancel_named(RuntimeOrigin::signed(BOB), name),
+				Error::<TestRuntime>::NotReminderOwner
+			);
+
+			assert_ok!(Reminder::cancel_named(RuntimeOrigin::signed(ALICE), name));
+			assert_eq!(Reminder::lookup(name), None);
+			assert!(Reminder::reminders(2).is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn name_released_after_execution() {
+		new_test_ext().execute_with(|| {
+			let name = [1; 32];
+			assert_ok!(Reminder::schedule_call(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				HIGHEST_PRIORITY,
+				Some(name),
+				remark(),
+			));
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Reminder::lookup(name), None);
+		})
+	}
+
+	#[test]
+	fn too_long() {
+		new_test_ext().execute_with(|| {
+			let call = RuntimeCall::System(frame_system::Call::remark_with_event {
+				remark: vec![0; MaxCallLen::get() as usize],
+			});
+			assert_noop!(
+				Reminder::schedule_call(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					HIGHEST_PRIORITY,
+					None,
+					Box::new(call),
+				),
+				Error::<TestRuntime>::CallTooLong
+			);
+		})
+	}
+
+	#[test]
+	fn too_heavy() {
+		new_test_ext().execute_with(|| {
+			// Enough for a plain reminder, but not for the remark
+			ReminderWeightLimit::set(Weight::from_parts(90_000, 0));
+			assert_noop!(
+				Reminder::schedule_call(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					HIGHEST_PRIORITY,
+					None,
+					remark(),
+				),
+				Error::<TestRuntime>::CallTooHeavy
+			);
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				2,
+				"test".as_bytes().to_vec(),
+			));
+		})
+	}
+}
+
+mod history {
+	use super::*;
+
+	#[test]
+	fn total_executed() {
+		new_test_ext().execute_with(|| {
+			for at in [2, 2, 3] {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					at,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			<Reminder as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Reminder::total_executed(), 2);
+			<Reminder as OnInitialize<u64>>::on_initialize(3);
+			assert_eq!(Reminder::event_counter(), 1);
+			assert_eq!(Reminder::total_executed(), 3);
+		})
+	}
+
+	#[test]
+	fn most_recent_first() {
+		new_test_ext().execute_with(|| {
+			for at in [2, 3, 4] {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					at,
+					"test".as_bytes().to_vec(),
+				));
+			}
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(BOB),
+				3,
+				"test".as_bytes().to_vec(),
+			));
+
+			for n in 2..=4 {
+				<Reminder as OnInitialize<u64>>::on_initialize(n);
+			}
+			// Only the last `MaxHistory` are kept
+			assert_eq!(Reminder::history(ALICE).into_inner(), vec![(2, 4), (1, 3)]);
+			assert_eq!(Reminder::history(BOB).into_inner(), vec![(3, 3)]);
+		})
+	}
+
+	#[test]
+	fn upcoming() {
+		new_test_ext().execute_with(|| {
+			for at in [2, 3, 5] {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					at,
+					"test".as_bytes().to_vec(),
+				));
+			}
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(BOB),
+				3,
+				"test".as_bytes().to_vec(),
+			));
+
+			assert_eq!(Reminder::upcoming(&ALICE, 3, 5), vec![(3, 1), (5, 2)]);
+			assert_eq!(Reminder::upcoming(&BOB, 2, 3), vec![(3, 3)]);
+			assert_eq!(Reminder::upcoming(&BOB, 4, 10), vec![]);
+
+			// Only `MaxUpcomingBlocks` blocks are walked
+			assert_ok!(Reminder::schedule_reminder(
+				RuntimeOrigin::signed(ALICE),
+				20,
+				"test".as_bytes().to_vec(),
+			));
+			assert_eq!(
+				Reminder::upcoming(&ALICE, 3, u64::MAX),
+				vec![(3, 1), (5, 2)]
 			);
+			assert_eq!(Reminder::upcoming(&ALICE, 15, 25), vec![(20, 4)]);
+		})
+	}
+}
+
+mod timestamp {
+	use super::*;
+
+	#[test]
+	fn fires_once_past() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_at_timestamp(
+				RuntimeOrigin::signed(ALICE),
+				2500,
+				"test".as_bytes().to_vec(),
+			));
+			System::assert_last_event(
+				crate::Event::TimedReminderSet(0, ALICE, 2500, "test".as_bytes().to_vec()).into(),
+			);
+			assert_eq!(Reminder::timed_reminders(2).len(), 1);
+			assert_eq!(Reminder::pending_buckets().into_inner(), vec![2]);
+
+			Timestamp::set_timestamp(2000);
 			<Reminder as OnFinalize<u64>>::on_finalize(2);
+			assert_eq!(Reminder::timed_reminders(2).len(), 1);
+			assert_eq!(Reminder::pending_buckets().into_inner(), vec![2]);
+			assert_eq!(Reminder::total_executed(), 0);
+
+			<Reminder as OnInitialize<u64>>::on_initialize(3);
+			Timestamp::set_timestamp(3000);
+			<Reminder as OnFinalize<u64>>::on_finalize(3);
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			System::assert_last_event(crate::Event::RemindersExecuteds(1).into());
+			assert!(Reminder::timed_reminders(2).is_empty());
+			assert!(Reminder::pending_buckets().is_empty());
+			assert_eq!(Reminder::total_executed(), 1);
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn in_the_past() {
+		new_test_ext().execute_with(|| {
+			Timestamp::set_timestamp(2000);
+			assert_noop!(
+				Reminder::schedule_at_timestamp(
+					RuntimeOrigin::signed(ALICE),
+					2000,
+					"test".as_bytes().to_vec(),
+				),
+				Error::<TestRuntime>::ReminderInThePast
+			);
+		})
+	}
+
+	#[test]
+	fn empty_buckets_skipped() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_at_timestamp(
+				RuntimeOrigin::signed(ALICE),
+				500,
+				"test".as_bytes().to_vec(),
+			));
+			assert_ok!(Reminder::schedule_at_timestamp(
+				RuntimeOrigin::signed(ALICE),
+				60_500,
+				"test2".as_bytes().to_vec(),
+			));
+
+			Timestamp::set_timestamp(100_000);
+			<Reminder as OnFinalize<u64>>::on_finalize(2);
+			System::assert_has_event(
+				crate::Event::Reminder(0, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			System::assert_has_event(
+				crate::Event::Reminder(1, ALICE, "test2".as_bytes().to_vec()).into(),
+			);
+			assert!(Reminder::pending_buckets().is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn buckets_walked_over_several_blocks() {
+		new_test_ext().execute_with(|| {
+			for moment in [500, 1500, 2500, 3500, 4500] {
+				assert_ok!(Reminder::schedule_at_timestamp(
+					RuntimeOrigin::signed(ALICE),
+					moment,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			Timestamp::set_timestamp(10_000);
+			<Reminder as OnFinalize<u64>>::on_finalize(2);
+			assert_eq!(Reminder::total_executed(), 4);
+			assert_eq!(Reminder::pending_buckets().into_inner(), vec![4]);
+
+			<Reminder as OnFinalize<u64>>::on_finalize(3);
+			System::assert_has_event(
+				crate::Event::Reminder(4, ALICE, "test".as_bytes().to_vec()).into(),
+			);
+			assert!(Reminder::pending_buckets().is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn cancel() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reminder::schedule_at_timestamp(
+				RuntimeOrigin::signed(ALICE),
+				2500,
+				"test".as_bytes().to_vec(),
+			));
+			assert_noop!(
+				Reminder::cancel_timed(RuntimeOrigin::signed(BOB), 2500, 0),
+				Error::<TestRuntime>::NotReminderOwner
+			);
+			assert_noop!(
+				Reminder::cancel_timed(RuntimeOrigin::signed(ALICE), 3500, 0),
+				Error::<TestRuntime>::ReminderNotFound
+			);
+
+			assert_ok!(Reminder::cancel_timed(
+				RuntimeOrigin::signed(ALICE),
+				2500,
+				0
+			));
+			System::assert_last_event(crate::Event::ReminderCancelled(0, ALICE).into());
+			assert!(Reminder::timed_reminders(2).is_empty());
+			assert!(Reminder::pending_buckets().is_empty());
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+		})
+	}
+
+	#[test]
+	fn upcoming() {
+		new_test_ext().execute_with(|| {
+			for (who, moment) in [(ALICE, 2500), (ALICE, 3500), (BOB, 2600), (ALICE, 7000)] {
+				assert_ok!(Reminder::schedule_at_timestamp(
+					RuntimeOrigin::signed(who),
+					moment,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			assert_eq!(
+				Reminder::upcoming_timed(&ALICE, 2000, 4000),
+				vec![(2500, 0), (3500, 1)]
+			);
+			assert_eq!(
+				Reminder::upcoming_timed(&ALICE, 3000, 10_000),
+				vec![(3500, 1), (7000, 3)]
+			);
+			assert_eq!(Reminder::upcoming_timed(&BOB, 0, 2000), vec![]);
+		})
+	}
+}
+
+mod bounds {
+	use super::*;
+
+	#[test]
+	fn in_the_past() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(3);
+			for at in [2, 3] {
+				assert_noop!(
+					Reminder::schedule_reminder(
+						RuntimeOrigin::signed(ALICE),
+						at,
+						"test".as_bytes().to_vec(),
+					),
+					Error::<TestRuntime>::ReminderInThePast
+				);
+			}
+		})
+	}
+
+	#[test]
+	fn message_too_long() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					vec![0; MaxMessageLen::get() as usize + 1],
+				),
+				Error::<TestRuntime>::MessageTooLong
+			);
+		})
+	}
+
+	#[test]
+	fn too_many_reminders() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..MaxRemindersPerBlock::get() {
+				assert_ok!(Reminder::schedule_reminder(
+					RuntimeOrigin::signed(ALICE),
+					2,
+					"test".as_bytes().to_vec(),
+				));
+			}
+
+			assert_noop!(
+				Reminder::schedule_reminder(
+					RuntimeOrigin::signed(BOB),
+					2,
+					"test".as_bytes().to_vec(),
+				),
+				Error::<TestRuntime>::TooManyReminders
+			);
+		})
+	}
+
+	#[test]
+	fn not_enough_for_deposit() {
+		new_test_ext().execute_with(|| {
+			assert!(
+				Reminder::schedule_reminder(
+					RuntimeOrigin::signed(3),
+					2,
+					"test".as_bytes().to_vec(),
+				)
+				.is_err()
+			);
 		})
 	}
 }
@@ -0,0 +1,47 @@
+use crate::{BalanceOf, Config};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{pallet_prelude::RuntimeDebug, weights::Weight, BoundedVec};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::TypeInfo;
+
+pub type ReminderId = u32;
+
+/// Reminders of a block are executed by increasing priority value
+pub type Priority = u8;
+pub const HIGHEST_PRIORITY: Priority = 0;
+pub const LOWEST_PRIORITY: Priority = 255;
+
+pub type TaskName = [u8; 32];
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Recurrence<BlockNumber> {
+	pub period: BlockNumber,
+	/// Number of times the reminder will still fire, the pending one included
+	pub remaining: u32,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub struct ReminderData<T: Config> {
+	pub id: ReminderId,
+	pub owner: T::AccountId,
+	pub message: BoundedVec<u8, T::MaxMessageLen>,
+	/// Reserved from `owner` and refunded when the reminder fires for the last time or is
+	/// cancelled
+	pub deposit: BalanceOf<T>,
+	pub recurrence: Option<Recurrence<BlockNumberFor<T>>>,
+	pub priority: Priority,
+	pub name: Option<TaskName>,
+	/// Dispatched with the owner as signed origin instead of emitting the message
+	pub call: Option<ScheduledCall<T>>,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub struct ScheduledCall<T: Config> {
+	pub encoded: BoundedVec<u8, T::MaxCallLen>,
+	/// Weight of the call when it was scheduled
+	pub weight: Weight,
+}
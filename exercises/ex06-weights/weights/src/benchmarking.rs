@@ -1,53 +1,58 @@
 use super::*;
-// got the "file not included in module tree" warning ?
-// look at the comment in Cargo.toml (in the feature section), and the one at the top of the lib.rs
-// file
-//
 use crate::Pallet as Weights;
-use frame_benchmarking::{account as benchmark_account, benchmarks};
+use frame_benchmarking::{benchmarks, whitelisted_caller};
 use frame_system::RawOrigin;
+use sp_std::vec;
+
+// Largest data stored by `benchmarked_store_maybe_hashed` in the benchmarks: 1MB
+const MAX_DATA_LEN: u32 = 1024 * 1024;
 
 benchmarks! {
-	/////////////////////// Part 2 - benchmarks ///////////////////////
+	/////////////////////// Part 1 - arbitrary weights ///////////////////////
 
-	//TODO: change this generic benchmark to benchmark the duplicate_and_store extrinsic
-	benchmark_name {
-		//this variable is a range, meaning the benchmark will be run with the different values of
-		//s, to evaluate the weight of this specific parameter
-		let s in 0 .. 1;
-		todo("change this range to something that makes sense for your benchmark");
+	verify_address {
+		// the caller is the stored address, so the call succeeds after reading it
+		let caller: T::AccountId = whitelisted_caller();
+		Acc::<T>::put(caller.clone());
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Acc::<T>::get(), Some(caller));
+	}
 
-		let root = todo!("get the root origin, to sign our transactions");
+	/////////////////////// Part 2 - benchmarks ///////////////////////
 
+	duplicate_and_store {
+		// the weight is linear in the number of elements stored
+		let s in 0 .. 1000;
 
-		// Now that we have all the parameters we need for our extrinsic's benchmark, we can call
-		// it:
-	}: extrinsic_name(root, 0, s)
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), 0, s)
 	verify {
-		// Run some verifications here.
-		// If something isn't right, the benchmark will throw an error and wont output values
-		assert_eq!(1, 0);
+		assert_eq!(VecDup::<T>::get().map(|vec| vec.len()), Some(s as usize));
 	}
 
-	/////////////////////// Part 3.A - conditional benchmarks ///////////////////////
+	/////////////////////// Part 3.B - conditional benchmarks ///////////////////////
+
 	store_maybe_hashed_true {
-		//TODO: prepare the datas for this benchmark (the account, the data, and the hash)
-		let root = todo!("get the root origin, to sign our transactions");
-		let data = todo!();
-		let hash = todo!();
-	}: store_maybe_hashed(root, data, hash)
+		// hashing is linear in the length of the data
+		let l in 0 .. MAX_DATA_LEN;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let data = vec![1; l as usize];
+	}: benchmarked_store_maybe_hashed(RawOrigin::Signed(caller), data.clone(), true)
 	verify {
-		//TODO: do some verification that your extrinsic did what it was supposed to do
+		assert_eq!(Data::<T>::get(), Some(blake2_256(&data).to_vec()));
 	}
 
 	store_maybe_hashed_false {
-		//TODO: prepare the datas for this benchmark (the account, the data, and the hash)
-		let root = todo!("get the root origin, to sign our transactions");
-		let data = todo!();
-		let hash = todo!();
-	}: store_maybe_hashed(root, data, hash)
+		// the data is stored as is
+		let l in 0 .. MAX_DATA_LEN;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let data = vec![1; l as usize];
+	}: benchmarked_store_maybe_hashed(RawOrigin::Signed(caller), data.clone(), false)
 	verify {
-		//TODO: do some verification that your extrinsic did what it was supposed to do
+		assert_eq!(Data::<T>::get(), Some(data));
 	}
 
 	impl_benchmark_test_suite!(Weights, crate::mock::new_test_ext(), crate::mock::Test);
@@ -10,11 +10,11 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-// uncomment the following lines to include the benchmarking.rs file in the module tree, if the
-// runtime-benchmarks feature is activated
-//
-// #[cfg(feature = "runtime-benchmarks")]
-// mod benchmarking;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
 
 use sp_std::vec::Vec;
 
@@ -27,7 +27,7 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type WeightInfo;
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
@@ -61,11 +61,9 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/////////////////////// Part 1 - arbitrary weights ///////////////////////
-		//TODO give this exctrinsic an arbitrary weight !
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::verify_address())]
 		pub fn verify_address(origin: OriginFor<T>) -> DispatchResult {
-			let who = ensure_signed(origin.clone())?;
-			ensure_root(origin)?;
+			let who = ensure_signed(origin)?;
 
 			// we do a read, this should be seen in the weight
 			let address = Self::acc();
@@ -80,8 +78,7 @@ pub mod pallet {
 		}
 
 		/////////////////////// Part 2 - benchmarks ///////////////////////
-		//TODO write a benchmark for this extrinsic in benchmarking.rs
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::duplicate_and_store(*count))]
 		pub fn duplicate_and_store(origin: OriginFor<T>, elem: u32, count: u32) -> DispatchResult {
 			ensure_signed(origin)?;
 
@@ -95,8 +92,11 @@ pub mod pallet {
 		}
 
 		/////////////////////// Part 3.A - conditional arbitrary weight ///////////////////////
-		//TODO give this extrinsic a weight of 100_000 if `hash` is true, or 10_000 otherwise
-		#[pallet::weight(0)]
+		#[pallet::weight(if *hash {
+			Weight::from_parts(100_000, 0)
+		} else {
+			Weight::from_parts(10_000, 0)
+		})]
 		pub fn store_maybe_hashed(
 			origin: OriginFor<T>,
 			data: Vec<u8>,
@@ -115,25 +115,30 @@ pub mod pallet {
 		}
 
 		/////////////////////// Part 3.B - conditional benchmark ///////////////////////
-		//TODO write two benchmarks for this extrinsic in benchmarking.rs, and then choose the
-		//corresponding one depending on the value of `hash`
-		//hint: look at this pallet's weights macros: https://github.com/paritytech/substrate/blob/master/frame/utility/src/lib.rs
-		#[pallet::weight(0)]
+		// the worst of both branches is charged upfront, and the one actually taken refunded
+		#[pallet::weight({
+			let len = data.len() as u32;
+			T::WeightInfo::store_maybe_hashed_true(len)
+				.max(T::WeightInfo::store_maybe_hashed_false(len))
+		})]
 		pub fn benchmarked_store_maybe_hashed(
 			origin: OriginFor<T>,
 			data: Vec<u8>,
 			hash: bool,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
 
-			if hash {
+			let len = data.len() as u32;
+			let actual_weight = if hash {
 				let hash = blake2_256(&data);
 				Data::<T>::put(hash.as_ref().to_vec());
+				T::WeightInfo::store_maybe_hashed_true(len)
 			} else {
 				Data::<T>::put(data);
-			}
+				T::WeightInfo::store_maybe_hashed_false(len)
+			};
 
-			Ok(())
+			Ok(Some(actual_weight).into())
 		}
 	}
 }
@@ -1,13 +1,25 @@
 use crate as pallet_weights;
-use crate::mock::*;
-use frame_support::weights::{GetDispatchInfo, RuntimeDbWeight};
+use crate::{mock::*, WeightInfo};
+use frame_support::{
+	dispatch::GetDispatchInfo,
+	weights::{RuntimeDbWeight, Weight},
+};
 
 #[test]
 fn verify_address_test() {
 	new_test_ext().execute_with(|| {
 		let db_weights: RuntimeDbWeight = <Test as frame_system::Config>::DbWeight::get();
 		let weight = pallet_weights::Call::<Test>::verify_address {}.get_dispatch_info().weight;
-		assert_eq!(weight, 10_000 + db_weights.reads(1));
+		assert_eq!(weight, Weight::from_parts(10_000, 0) + db_weights.reads(1));
+	});
+}
+
+#[test]
+fn verify_address_stored_caller_test() {
+	new_test_ext().execute_with(|| {
+		pallet_weights::Acc::<Test>::put(1);
+		assert!(pallet_weights::Pallet::<Test>::verify_address(RuntimeOrigin::signed(1)).is_ok());
+		assert!(pallet_weights::Pallet::<Test>::verify_address(RuntimeOrigin::signed(2)).is_err());
 	});
 }
 
@@ -25,8 +37,8 @@ fn duplicate_test() {
 		.get_dispatch_info()
 		.weight;
 
-		assert!(weight1 < weight2);
-		assert!(weight1 > db_weights.writes(1));
+		assert!(weight1.ref_time() < weight2.ref_time());
+		assert!(weight1.ref_time() > db_weights.writes(1).ref_time());
 	});
 }
 
@@ -46,8 +58,8 @@ fn store_maybe_hashed_test() {
 		.get_dispatch_info()
 		.weight;
 
-		assert_eq!(weight1, 100_000);
-		assert_eq!(weight2, 10_000);
+		assert_eq!(weight1, Weight::from_parts(100_000, 0));
+		assert_eq!(weight2, Weight::from_parts(10_000, 0));
 	});
 }
 
@@ -62,12 +74,62 @@ fn benchmarked_store_maybe_hashed_test() {
 		.get_dispatch_info()
 		.weight;
 		let weight2 = pallet_weights::Call::<Test>::benchmarked_store_maybe_hashed {
-			data: long_vec,
+			data: long_vec.clone(),
+			hash: false,
+		}
+		.get_dispatch_info()
+		.weight;
+
+		// the worst case is charged whatever the branch
+		assert_eq!(weight1, weight2);
+
+		let actual1 = pallet_weights::Pallet::<Test>::benchmarked_store_maybe_hashed(
+			RuntimeOrigin::signed(1),
+			long_vec.clone(),
+			true,
+		)
+		.unwrap()
+		.actual_weight
+		.unwrap();
+		let actual2 = pallet_weights::Pallet::<Test>::benchmarked_store_maybe_hashed(
+			RuntimeOrigin::signed(1),
+			long_vec,
+			false,
+		)
+		.unwrap()
+		.actual_weight
+		.unwrap();
+
+		assert_eq!(
+			actual1,
+			pallet_weights::SubstrateWeight::<Test>::store_maybe_hashed_true(100000)
+		);
+		assert_eq!(
+			actual2,
+			pallet_weights::SubstrateWeight::<Test>::store_maybe_hashed_false(100000)
+		);
+		assert!(actual1.all_lte(weight1));
+		assert!(actual2.all_lte(weight2));
+		assert_ne!(actual1, actual2);
+	});
+}
+
+#[test]
+fn store_maybe_hashed_length_test() {
+	new_test_ext().execute_with(|| {
+		let short = pallet_weights::Call::<Test>::benchmarked_store_maybe_hashed {
+			data: vec![1; 32],
+			hash: false,
+		}
+		.get_dispatch_info()
+		.weight;
+		let long = pallet_weights::Call::<Test>::benchmarked_store_maybe_hashed {
+			data: vec![1; 1024 * 1024],
 			hash: false,
 		}
 		.get_dispatch_info()
 		.weight;
 
-		assert!(weight1 > weight2);
+		assert!(short.ref_time() < long.ref_time());
 	});
 }
@@ -0,0 +1,82 @@
+//! Weights for `pallet_weight`
+//!
+//! These are hand-written placeholders, not benchmark results. They follow the layout of the
+//! benchmark CLI output so the file it generates can replace this one, see the README of the
+//! exercise for the command to run.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_weight`.
+pub trait WeightInfo {
+	fn verify_address() -> Weight;
+	fn duplicate_and_store(s: u32, ) -> Weight;
+	fn store_maybe_hashed_true(l: u32, ) -> Weight;
+	fn store_maybe_hashed_false(l: u32, ) -> Weight;
+}
+
+/// Placeholder weights for `pallet_weight`, to be replaced by benchmarked ones.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Weights::Acc` (r:1 w:0)
+	fn verify_address() -> Weight {
+		Weight::from_parts(10_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Weights::VecDup` (r:0 w:1)
+	/// The range of component `s` is `[0, 1000]`.
+	fn duplicate_and_store(s: u32, ) -> Weight {
+		Weight::from_parts(3_400_000, 0)
+			.saturating_add(Weight::from_parts(1_400, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Weights::Data` (r:0 w:1)
+	/// The range of component `l` is `[0, 1048576]`.
+	fn store_maybe_hashed_true(l: u32, ) -> Weight {
+		Weight::from_parts(5_500_000, 0)
+			.saturating_add(Weight::from_parts(1_100, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Weights::Data` (r:0 w:1)
+	/// The range of component `l` is `[0, 1048576]`.
+	fn store_maybe_hashed_false(l: u32, ) -> Weight {
+		Weight::from_parts(4_200_000, 0)
+			.saturating_add(Weight::from_parts(2_300, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `Weights::Acc` (r:1 w:0)
+	fn verify_address() -> Weight {
+		Weight::from_parts(10_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Weights::VecDup` (r:0 w:1)
+	/// The range of component `s` is `[0, 1000]`.
+	fn duplicate_and_store(s: u32, ) -> Weight {
+		Weight::from_parts(3_400_000, 0)
+			.saturating_add(Weight::from_parts(1_400, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Weights::Data` (r:0 w:1)
+	/// The range of component `l` is `[0, 1048576]`.
+	fn store_maybe_hashed_true(l: u32, ) -> Weight {
+		Weight::from_parts(5_500_000, 0)
+			.saturating_add(Weight::from_parts(1_100, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Weights::Data` (r:0 w:1)
+	/// The range of component `l` is `[0, 1048576]`.
+	fn store_maybe_hashed_false(l: u32, ) -> Weight {
+		Weight::from_parts(4_200_000, 0)
+			.saturating_add(Weight::from_parts(2_300, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}